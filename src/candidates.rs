@@ -0,0 +1,618 @@
+//! Mutation candidates: the code each operator family mutates.
+//!
+//! Every family has its own function, which returns the candidates starting at a lexeme along
+//! with the spans of source they replace. `candidates()` combines them.
+
+use crate::{
+    lexer::{Lexeme, LexemeKind},
+    syntax::{
+        binary_operands, call_arguments, call_span, condition_span, constraint_span, index_span,
+        is_cast_operand, is_cast_type, is_field_access, literal_is_mutable, loop_range,
+        statement_span, SyntaxTree,
+    },
+    token::{raw_string_as_token, Token},
+    utils::Declarations,
+};
+use std::ops::Range;
+
+/// A token to mutate and the span of source it replaces
+pub type Candidate = (Token, Range<usize>);
+
+/// A family's search for the candidates starting at the lexeme at an index
+type Family = fn(&SourceFile, usize) -> Vec<Candidate>;
+
+/// The families, in the order their candidates are reported
+const FAMILIES: [Family; 14] = [
+    operators,
+    operand_swaps,
+    literals,
+    booleans,
+    casts,
+    field_accesses,
+    conditions,
+    constraints,
+    statements,
+    hints,
+    argument_swaps,
+    return_values,
+    loop_ranges,
+    array_indexes,
+];
+
+/// A lexed and parsed source file, with the declarations its call sites and field accesses use
+pub struct SourceFile<'a> {
+    contents: &'a str,
+    lexemes: &'a [Lexeme],
+    tree: &'a SyntaxTree,
+    declarations: &'a Declarations,
+    // The bounds and operators of `for` loop ranges
+    ranges: Vec<Candidate>,
+    // Array indexes
+    indexes: Vec<Candidate>,
+}
+
+impl<'a> SourceFile<'a> {
+    /// `..` is only made inclusive if `inclusive_ranges`, see
+    /// `LanguageConfig::supports_inclusive_ranges()`
+    pub fn new(
+        contents: &'a str,
+        lexemes: &'a [Lexeme],
+        tree: &'a SyntaxTree,
+        declarations: &'a Declarations,
+        inclusive_ranges: bool,
+    ) -> Self {
+        let text = |span: &Range<usize>| contents[span.clone()].to_string();
+        let ranges = (0..lexemes.len())
+            .filter_map(|index| loop_range(contents, lexemes, index))
+            .flat_map(|(start, operator, end)| {
+                let operator_token = match &contents[operator.clone()] {
+                    ".." => inclusive_ranges.then_some(Token::DotDot),
+                    _ => Some(Token::DotDotEqual),
+                };
+                [
+                    Some((Token::RangeBound(text(&start)), start)),
+                    operator_token.map(|token| (token, operator)),
+                    Some((Token::RangeBound(text(&end)), end)),
+                ]
+            })
+            .flatten()
+            .collect();
+        let indexes = (0..lexemes.len())
+            .filter_map(|index| {
+                let span = index_span(contents, lexemes, index)?;
+                Some((Token::Index(text(&span)), span))
+            })
+            .collect();
+
+        SourceFile {
+            contents,
+            lexemes,
+            tree,
+            declarations,
+            ranges,
+            indexes,
+        }
+    }
+
+    fn text(&self, index: usize) -> &str {
+        self.lexemes[index].text(self.contents)
+    }
+
+    fn span(&self, index: usize) -> Range<usize> {
+        self.lexemes[index].span()
+    }
+
+    fn is_kind(&self, index: usize, kind: LexemeKind) -> bool {
+        self.lexemes[index].kind() == kind
+    }
+
+    /// The source at `span` as a token
+    fn token(&self, span: Range<usize>, token: fn(String) -> Token) -> Candidate {
+        (token(self.contents[span.clone()].to_string()), span)
+    }
+
+    /// The call arguments or operands at `left` and `right` swapped, keeping the text between
+    fn swap(&self, left: Range<usize>, right: Range<usize>) -> Candidate {
+        let span = left.start..right.end;
+        let swapped = format!(
+            "{}{}{}",
+            &self.contents[right.clone()],
+            &self.contents[left.end..right.start],
+            &self.contents[left]
+        );
+        (
+            Token::Swap(self.contents[span.clone()].to_string(), swapped),
+            span,
+        )
+    }
+}
+
+/// Every candidate starting at the lexeme at `index`. Types are never mutated.
+pub fn candidates(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    if file.lexemes[index].in_type() {
+        return vec![];
+    }
+    FAMILIES
+        .iter()
+        .flat_map(|family| family(file, index))
+        .collect()
+}
+
+/// Arithmetic, bitwise, relational, logical, shorthand and unary operators
+fn operator(file: &SourceFile, index: usize) -> Option<Token> {
+    if !file.is_kind(index, LexemeKind::Punct) {
+        return None;
+    }
+    raw_string_as_token(file.text(index))
+        // `!` is the only prefix operator that is mutated, all others must be infix
+        .filter(|token| (*token == Token::Bang) != file.lexemes[index].binary())
+}
+
+pub fn operators(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    operator(file, index)
+        .map(|token| (token, file.span(index)))
+        .into_iter()
+        .collect()
+}
+
+/// The operands of `-`, `/`, `%`, `<<` and `>>`, swapped
+pub fn operand_swaps(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    operator(file, index)
+        .filter(|token| token.is_non_commutative())
+        .and_then(|_| binary_operands(file.contents, file.lexemes, index))
+        .map(|(left, right)| file.swap(left, right))
+        .into_iter()
+        .collect()
+}
+
+/// Integer literals, other than whole loop bounds and array indexes, which are shifted instead
+pub fn literals(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let span = file.span(index);
+    if !file.is_kind(index, LexemeKind::Int)
+        || !literal_is_mutable(file.contents, file.lexemes, index)
+        || file
+            .ranges
+            .iter()
+            .chain(&file.indexes)
+            .any(|(_, part)| *part == span)
+    {
+        return vec![];
+    }
+    vec![(Token::Literal(file.text(index).to_string()), span)]
+}
+
+pub fn booleans(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let token = match file.text(index) {
+        "true" => Token::True,
+        "false" => Token::False,
+        _ => return vec![],
+    };
+    if !file.is_kind(index, LexemeKind::Ident) {
+        return vec![];
+    }
+    vec![(token, file.span(index))]
+}
+
+/// The types of `as` casts. A cast that is an operand of a binary operator keeps its type, so
+/// that the other operand still matches it, and is only narrowed inside
+pub fn casts(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    if !is_cast_type(file.contents, file.lexemes, index) {
+        return vec![];
+    }
+    let cast_type = file.text(index).to_string();
+    let token = match is_cast_operand(file.contents, file.lexemes, index) {
+        true => Token::CastOperand(cast_type),
+        false => Token::Cast(cast_type),
+    };
+    vec![(token, file.span(index))]
+}
+
+/// Struct field accesses, one per other field of the same type in the struct
+pub fn field_accesses(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    if !is_field_access(file.contents, file.lexemes, index) {
+        return vec![];
+    }
+    let field = file.text(index);
+    file.declarations
+        .fields
+        .get(field)
+        .into_iter()
+        .flatten()
+        .map(|other| {
+            let token = Token::FieldAccess(field.to_string(), other.clone());
+            (token, file.span(index))
+        })
+        .collect()
+}
+
+/// The conditions of `if` and `assert`
+pub fn conditions(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    condition_span(file.contents, file.lexemes, index)
+        .map(|span| file.token(span, Token::Condition))
+        .into_iter()
+        .collect()
+}
+
+/// `assert`, `assert_eq` and `constrain` statements
+pub fn constraints(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    constraint_span(file.contents, file.lexemes, index)
+        .map(|span| file.token(span, Token::Constraint))
+        .into_iter()
+        .collect()
+}
+
+/// Assignments and calls whose value is discarded
+pub fn statements(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    statement_span(file.contents, file.lexemes, index)
+        .map(|span| file.token(span, Token::Statement))
+        .into_iter()
+        .collect()
+}
+
+/// Calls to unconstrained functions from constrained code. Only hints used by constrained code
+/// can leave a circuit unsound.
+pub fn hints(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let Some(signature) = file
+        .declarations
+        .signatures
+        .get(file.text(index))
+        .filter(|signature| signature.unconstrained)
+    else {
+        return vec![];
+    };
+    let in_unconstrained = file
+        .tree
+        .enclosing_function(file.span(index).start)
+        .is_some_and(|function| function.is_unconstrained());
+    if in_unconstrained {
+        return vec![];
+    }
+
+    let Some(return_type) = signature
+        .return_type
+        .clone()
+        .filter(|return_type| file.declarations.has_default(return_type))
+    else {
+        return vec![];
+    };
+    call_span(file.contents, file.lexemes, index)
+        .map(|call| {
+            let hint = Token::Hint(file.contents[call.clone()].to_string(), return_type);
+            (hint, call)
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Adjacent call arguments of the same type, swapped
+pub fn argument_swaps(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let Some(signature) = file.declarations.signatures.get(file.text(index)) else {
+        return vec![];
+    };
+    let arguments = call_arguments(file.contents, file.lexemes, index)
+        .filter(|arguments| signature.params.len() == arguments.len())
+        .unwrap_or_default();
+    let params = &signature.params;
+    arguments
+        .windows(2)
+        .enumerate()
+        .filter(|(n, pair)| {
+            params[*n] == params[n + 1]
+                && file.contents[pair[0].clone()] != file.contents[pair[1].clone()]
+        })
+        .map(|(_, pair)| file.swap(pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+/// The value a non-test function returns, replaced as a whole, if its type has a default
+pub fn return_values(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let start = file.span(index).start;
+    file.tree
+        .functions()
+        .into_iter()
+        .filter(|function| !function.is_test())
+        .filter_map(|function| Some((function.tail()?, function.return_type()?)))
+        .filter(|(tail, return_type)| {
+            tail.start == start && file.declarations.has_default(return_type)
+        })
+        .map(|(tail, return_type)| {
+            let value = file.contents[tail.clone()].to_string();
+            (Token::ReturnValue(value, return_type.to_string()), tail)
+        })
+        .collect()
+}
+
+/// The bounds and operators of `for` loop ranges
+pub fn loop_ranges(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let start = file.span(index).start;
+    file.ranges
+        .iter()
+        .filter(|(_, part)| part.start == start)
+        .cloned()
+        .collect()
+}
+
+pub fn array_indexes(file: &SourceFile, index: usize) -> Vec<Candidate> {
+    let start = file.span(index).start;
+    file.indexes
+        .iter()
+        .filter(|(_, part)| part.start == start)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::lex, syntax::parse, utils::Signature};
+    use std::collections::{HashMap, HashSet};
+
+    /// The candidates a family finds in the source, as tokens and the source they replace
+    fn find(source: &str, declarations: &Declarations, family: Family) -> Vec<(Token, String)> {
+        let lexemes = lex(source);
+        let tree = parse(source, &lexemes);
+        let file = SourceFile::new(source, &lexemes, &tree, declarations, true);
+        (0..lexemes.len())
+            .filter(|&index| !lexemes[index].in_type())
+            .flat_map(|index| family(&file, index))
+            .map(|(token, span)| (token, source[span].to_string()))
+            .collect()
+    }
+
+    fn found(source: &str, family: Family) -> Vec<(Token, String)> {
+        find(source, &Declarations::default(), family)
+    }
+
+    fn signature(params: &[&str], return_type: &str, unconstrained: bool) -> Signature {
+        Signature {
+            params: params.iter().map(|param| param.to_string()).collect(),
+            return_type: Some(return_type.to_string()),
+            unconstrained,
+        }
+    }
+
+    fn spans(candidates: Vec<(Token, String)>) -> Vec<String> {
+        candidates.into_iter().map(|(_, text)| text).collect()
+    }
+
+    #[test]
+    fn test_operators() {
+        let candidates = found("fn f(a: u8) -> bool { let b = !(a - 1 < 2); b }", operators);
+        let tokens: Vec<Token> = candidates.into_iter().map(|(token, _)| token).collect();
+        assert_eq!(tokens, vec![Token::Bang, Token::Minus, Token::Less]);
+        // A prefix `-` isn't mutated
+        assert!(found("fn f() { let a = -1; }", operators).is_empty());
+    }
+
+    #[test]
+    fn test_operand_swaps() {
+        assert_eq!(
+            found("fn f() { let a = x / y + z - w * (u - v); }", operand_swaps),
+            vec![
+                (
+                    Token::Swap("x / y".to_string(), "y / x".to_string()),
+                    "x / y".to_string()
+                ),
+                (
+                    Token::Swap("u - v".to_string(), "v - u".to_string()),
+                    "u - v".to_string()
+                ),
+            ]
+        );
+        assert!(found("fn f() { let a = x + y * z; }", operand_swaps).is_empty());
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(
+            spans(found(
+                "fn f(t: (u8, u8)) { let a = [0; 4]; for i in 0..n { a[2] = t.0 + 7; } }",
+                literals
+            )),
+            // Not the array length, the loop bound, the index or the tuple index
+            vec!["0", "7"]
+        );
+    }
+
+    #[test]
+    fn test_booleans() {
+        assert_eq!(
+            found("fn f() { let a = true & !false; }", booleans),
+            vec![
+                (Token::True, "true".to_string()),
+                (Token::False, "false".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_casts() {
+        assert_eq!(
+            found(
+                "fn f() { let a = x as u8; assert(b as u64 > c as u64); }",
+                casts
+            ),
+            vec![
+                (Token::Cast("u8".to_string()), "u8".to_string()),
+                (Token::CastOperand("u64".to_string()), "u64".to_string()),
+                (Token::CastOperand("u64".to_string()), "u64".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_casts_in_fixture() {
+        let source = include_str!("../test/languages/noir/src/main.nr");
+        let candidates = found(source, casts);
+        // Every cast there is an operand of a comparison, i.e: `c as u64 > x as u64`
+        assert!(!candidates.is_empty());
+        assert!(candidates
+            .iter()
+            .all(|(token, text)| *token == Token::CastOperand("u64".to_string()) && text == "u64"));
+    }
+
+    #[test]
+    fn test_field_accesses() {
+        let declarations = Declarations {
+            fields: HashMap::from([("x".to_string(), vec!["y".to_string()])]),
+            ..Default::default()
+        };
+        assert_eq!(
+            find(
+                "fn f(p: Point) { let a = p.x + p.z + p.x(); }",
+                &declarations,
+                field_accesses
+            ),
+            vec![(
+                Token::FieldAccess("x".to_string(), "y".to_string()),
+                "x".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_conditions() {
+        assert_eq!(
+            spans(found(
+                "fn f() { if a == b { assert(c); } if true { } }",
+                conditions
+            )),
+            vec!["a == b", "c", "true"]
+        );
+    }
+
+    #[test]
+    fn test_constraints() {
+        assert_eq!(
+            spans(found(
+                "fn f() { assert(a); assert_eq(a, b); constrain a == b; let ok = assert(a); }",
+                constraints
+            )),
+            vec!["assert(a);", "assert_eq(a, b);", "constrain a == b;"]
+        );
+    }
+
+    #[test]
+    fn test_statements() {
+        assert_eq!(
+            spans(found(
+                "fn f() { let mut c = 1; c = 2; v.push(c); unsafe { log(c); } c }",
+                statements
+            )),
+            vec!["c = 2;", "v.push(c);", "log(c);"]
+        );
+    }
+
+    #[test]
+    fn test_hints() {
+        let declarations = Declarations {
+            signatures: HashMap::from([
+                ("sqrt".to_string(), signature(&["Field"], "Field", true)),
+                ("add".to_string(), signature(&["Field"], "Field", false)),
+                ("line".to_string(), signature(&["Field"], "Line", true)),
+            ]),
+            ..Default::default()
+        };
+        // `Line` has no default to replace the result with
+        let source = "fn f(x: Field) -> Field { let r = unsafe { sqrt(x) }; add(r + line(x).a) }
+            unconstrained fn g(x: Field) -> Field { sqrt(x) }";
+        assert_eq!(
+            find(source, &declarations, hints),
+            vec![(
+                Token::Hint("sqrt(x)".to_string(), "Field".to_string()),
+                "sqrt(x)".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_argument_swaps() {
+        let declarations = Declarations {
+            signatures: HashMap::from([(
+                "g".to_string(),
+                signature(&["u8", "u8", "bool"], "u8", false),
+            )]),
+            ..Default::default()
+        };
+        assert_eq!(
+            spans(find(
+                "fn f() { g(a, b, c); g(a, a, c); g(a, b); }",
+                &declarations,
+                argument_swaps
+            )),
+            vec!["a, b"]
+        );
+    }
+
+    #[test]
+    fn test_return_values() {
+        assert_eq!(
+            found(
+                "fn f(a: Field) -> Field { a + 1 } fn g() { 1 } #[test] fn t() -> u8 { 2 }",
+                return_values
+            ),
+            vec![(
+                Token::ReturnValue("a + 1".to_string(), "Field".to_string()),
+                "a + 1".to_string()
+            )]
+        );
+
+        // Only types with a default value
+        let declarations = Declarations {
+            defaults: HashSet::from(["Point".to_string()]),
+            ..Default::default()
+        };
+        let source = "fn f() -> Point { Point::new() } fn g() -> Line { Line::new() }";
+        assert_eq!(
+            spans(find(source, &declarations, return_values)),
+            vec!["Point::new()"]
+        );
+    }
+
+    #[test]
+    fn test_loop_ranges() {
+        let source = "fn f() { for i in 0..n + 1 { } }";
+        assert_eq!(
+            found(source, loop_ranges),
+            vec![
+                (Token::RangeBound("0".to_string()), "0".to_string()),
+                (Token::DotDot, "..".to_string()),
+                (Token::RangeBound("n + 1".to_string()), "n + 1".to_string()),
+            ]
+        );
+
+        // `..` stays exclusive when the compiler doesn't accept `..=`
+        let lexemes = lex(source);
+        let tree = parse(source, &lexemes);
+        let declarations = Declarations::default();
+        let file = SourceFile::new(source, &lexemes, &tree, &declarations, false);
+        assert!(!(0..lexemes.len())
+            .flat_map(|index| loop_ranges(&file, index))
+            .any(|(token, _)| token == Token::DotDot));
+    }
+
+    #[test]
+    fn test_array_indexes() {
+        assert_eq!(
+            spans(found(
+                "fn f() { let a = v[i] + w[0][j - 1]; }",
+                array_indexes
+            )),
+            vec!["i", "0", "j - 1"]
+        );
+    }
+
+    #[test]
+    fn test_candidates_skip_types() {
+        let source = "fn f(a: [u8; 2]) -> u8 { a[0] }";
+        let lexemes = lex(source);
+        let tree = parse(source, &lexemes);
+        let declarations = Declarations::default();
+        let file = SourceFile::new(source, &lexemes, &tree, &declarations, true);
+        let all: Vec<String> = (0..lexemes.len())
+            .flat_map(|index| candidates(&file, index))
+            .map(|(_, span)| source[span].to_string())
+            .collect();
+        assert_eq!(all, vec!["a[0]", "0"]);
+    }
+}
@@ -1,4 +1,12 @@
-use crate::{config::config, handlers, languages::common::Language, reporter::print_scan_results};
+use crate::{
+    config::config,
+    formats::{write_report, OutputFormat, Report},
+    handlers::{self, mutator::HigherOrder},
+    languages::common::Language,
+    operators::{Mode, OperatorFamily, Substitution},
+    reporter::{operators_table, print_scan_results, progress, progress_to_stderr},
+    token::MutationSet,
+};
 use clap::Parser;
 use colored::*;
 use std::io::Result;
@@ -9,6 +17,8 @@ pub enum Subcommand {
     Scan,
     /// Apply mutations and run the test suite against each mutant
     Mutate,
+    /// List the mutation operators, their families and replacements
+    Operators,
 }
 
 /// Mutate Noir code and run tests against each mutation.
@@ -18,18 +28,60 @@ pub struct Args {
     /// The target language
     #[clap(short, long)]
     language: Option<Language>,
-    /// Enable randomized mutatant generation
+    /// Generate one mutant per token, picked at random from its replacements in the mutation set
     #[clap(short, long, default_value = "false")]
     pub random: bool,
     /// The path to the source files directory
     #[clap(short, long, default_value = ".")]
     pub source_path: std::path::PathBuf,
+    /// Only mutate code in this function, given by name or item path, i.e: `add` or `math::add`
+    #[clap(long)]
+    pub function: Option<String>,
     /// The path to the output file (.md extension recommended)
     #[clap(short = 'o', long)]
     pub output_path: Option<std::path::PathBuf>,
+    /// What to hunt for: standard (operator, literal and condition mutants), constraints
+    /// (delete each assert, assert_eq and constrain statement to find under-constrained code) or
+    /// hints (tamper with the results of unconstrained calls to find hints that are never checked)
+    #[clap(long, default_value = "standard")]
+    pub mode: Mode,
+    /// The mutants to generate per operator: minimal (one replacement) or exhaustive (every replacement)
+    #[clap(long, default_value = "minimal")]
+    pub mutation_set: MutationSet,
+    /// Only mutate these operator families, comma separated, i.e: `relational,logical`.
+    /// Run `hunter operators` to list every family
+    #[clap(long, value_delimiter = ',')]
+    pub operators: Option<Vec<OperatorFamily>>,
+    /// Also combine pairs of mutants in the same function into second-order mutants:
+    /// same-function (every pair, found when scanning) or killed (only pairs of mutants that
+    /// were each killed, run after the first-order mutants)
+    #[clap(long)]
+    pub higher_order: Option<HigherOrder>,
+    /// Never apply this substitution, i.e: `--skip '% -> *'`. May be repeated
+    #[clap(long)]
+    pub skip: Vec<Substitution>,
+    /// The path to the config file. Defaults to hunter.toml in the current directory, if it exists
+    #[clap(long)]
+    pub config: Option<std::path::PathBuf>,
+    /// The report format: text, json, stryker, html, sarif, junit
+    #[clap(short, long, default_value = "text")]
+    pub format: OutputFormat,
     /// Display information about the program
     #[clap(short, long)]
     info: bool,
+    /// Seconds a mutant's build or test run may take before it is killed and marked as timed out.
+    /// Defaults to the baseline build or test duration times the timeout multiplier
+    #[clap(long)]
+    pub timeout: Option<u64>,
+    /// Multiple of the baseline build and test durations used as the default timeouts
+    #[clap(long, default_value = "3.0")]
+    pub timeout_multiplier: f64,
+    /// Leave timed out mutants out of the mutation score instead of counting them as killed
+    #[clap(long)]
+    pub exclude_timeouts: bool,
+    /// Leave unbuildable mutants out of the mutation score instead of counting them as killed
+    #[clap(long)]
+    pub exclude_unbuildable: bool,
     /// Run in debug mode. Requires manually deleting the temp directory!
     #[clap(short, long)]
     pub debug: bool,
@@ -40,9 +92,10 @@ pub struct Args {
 
 pub async fn run_cli() -> Result<()> {
     let args = Args::parse();
+    progress_to_stderr(args.format != OutputFormat::Text);
 
     if args.random {
-        println!("{}", "Random mutant generation activated...".yellow());
+        progress("Random mutant generation activated...".yellow());
     }
 
     if args.info {
@@ -53,7 +106,7 @@ pub async fn run_cli() -> Result<()> {
     let language = if let Some(lang) = args.language.clone() {
         lang
     } else {
-        println!("{}", "No language specified. Defaulting to Noir.".yellow());
+        progress("No language specified. Defaulting to Noir.".yellow());
         Language::Noir
     };
 
@@ -62,8 +115,10 @@ pub async fn run_cli() -> Result<()> {
     match args.subcommand {
         Some(Subcommand::Scan) => {
             let result = handlers::scanner::scan(args.clone(), config.clone_box());
-            if let Ok(result) = result {
-                print_scan_results(&mut result.clone(), config)
+            if let Ok(mut result) = result {
+                print_scan_results(&mut result.clone(), config.clone_box(), None)?;
+                let report = Report::new("scan", result.mutants(), &*config, &args);
+                write_report(&args.format, &report, args.output_path.as_deref())
             } else {
                 Err(result.unwrap_err())
             }
@@ -71,12 +126,18 @@ pub async fn run_cli() -> Result<()> {
         Some(Subcommand::Mutate) => {
             let result = handlers::scanner::scan(args.clone(), config.clone_box());
             if let Ok(mut result) = result {
-                let _ = print_scan_results(&mut result.clone(), config.clone_box());
-                handlers::mutator::mutate(args.clone(), config.clone_box(), &mut result)
+                let baseline = handlers::baseline::baseline(result.mutants(), &*config)?;
+                let _ =
+                    print_scan_results(&mut result.clone(), config.clone_box(), Some(&baseline));
+                handlers::mutator::mutate(args.clone(), config.clone_box(), &mut result, &baseline)
             } else {
                 Err(result.unwrap_err())
             }
         }
+        Some(Subcommand::Operators) => {
+            operators_table().printstd();
+            Ok(())
+        }
         None => {
             info_message();
             Ok(())
@@ -98,6 +159,7 @@ If a mutant causes a test to fail, it is considered to be Killed.
 If a mutant does not cause a test to fail, it is considered Survived.
 Other possible states for a mutant include Pending (generally indicates a possible internal bug in Hunter itself)
 or Unbuildable (the mutant introduces invalid syntax, failing constraints or other compiler errors).
+A mutant whose build or test run exceeds the timeout (i.e: an infinite loop) is marked as Timeout.
 
 Hunter will then calculate a mutation score, where 100% is what you ideally want to see.
 ".cyan());
@@ -1,9 +1,13 @@
 use crate::languages::common::Language;
-use crate::{handlers::mutator::Mutant, languages};
+use crate::{handlers::mutator::Mutant, languages, lexer::Lexeme, syntax::SyntaxTree};
 use fs_extra::error::Error;
 use regex::Regex;
-use std::sync::Mutex;
-use std::{io, path::PathBuf, process};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 use tempfile::TempDir;
 
 pub trait LanguageConfig {
@@ -11,23 +15,40 @@ pub trait LanguageConfig {
     fn name(&self) -> &'static str;
     fn ext(&self) -> &'static str;
     fn test_runner(&self) -> &'static str;
+    fn test_runner_version(&self) -> Option<String>;
+    /// Whether the installed compiler accepts inclusive `..=` ranges
+    fn supports_inclusive_ranges(&self) -> bool;
     fn test_command(&self) -> &'static str;
     fn build_command(&self) -> &'static str;
     fn manifest_name(&self) -> &'static str;
     fn excluded_dirs(&self) -> Vec<&'static str>;
     fn filter_tests(&self) -> bool;
     fn test_regex(&self) -> Option<Regex>;
-    fn comment_regex(&self) -> Regex;
-    fn literal_regex(&self) -> Regex;
+    fn lex(&self, source: &str) -> Vec<Lexeme>;
+    fn syntax_tree(&self, source: &str, lexemes: &[Lexeme]) -> SyntaxTree;
+    fn module_path(&self, project_root: &Path, src_path: &Path) -> Vec<String>;
+    /// Captures each test's name, `ok` or `FAIL` and, if the runner prints it, the test's
+    /// duration and its unit, `ms` or `s`
+    fn test_outcome_regex(&self) -> Regex;
     fn setup_test_infrastructure(&self) -> Result<TempDir, Error>;
+    fn copy_project(&self, project_root: &Path, workspace: &Path) -> io::Result<()>;
     fn copy_src_file(
         &self,
-        temp_dir: &TempDir,
+        workspace: &Path,
+        project_root: &Path,
         mutant: &Mutant,
-        mutex: Option<&Mutex<()>>,
     ) -> io::Result<PathBuf>;
-    fn test_mutant_project(&self) -> Box<process::Output>;
-    fn build_mutant_project(&self) -> Box<process::Output>;
+    /// Returns `None` if the test run was killed because it exceeded the timeout
+    fn test_mutant_project(
+        &self,
+        dir: &Path,
+        timeout: Option<Duration>,
+    ) -> Option<Box<process::Output>>;
+    fn build_mutant_project(
+        &self,
+        dir: &Path,
+        timeout: Option<Duration>,
+    ) -> Option<Box<process::Output>>;
     fn clone_box(&self) -> Box<dyn LanguageConfig + Send + Sync>;
 }
 
@@ -1,11 +1,14 @@
 use crate::{
-    config::LanguageConfig, handlers::mutator::Mutant, token::token_as_bytes, utils::replace_bytes,
+    config::LanguageConfig,
+    handlers::mutator::Mutant,
+    token::{token_as_bytes, Token},
+    utils::replace_bytes,
 };
 use colored::*;
 use dialoguer::Confirm;
 use std::{
-    fs::{File, OpenOptions},
-    io::{Read, Result, Write},
+    fs,
+    io::{Error, ErrorKind, Result},
     path::{Path, PathBuf},
 };
 
@@ -87,7 +90,7 @@ pub fn find_source_file_paths<'a>(
                 }
             } else if path_buf
                 .extension()
-                .map_or(false, |extension| extension == config.ext())
+                .is_some_and(|extension| extension == config.ext())
             {
                 let relative_path = path_buf.strip_prefix(base_dir).unwrap_or(&path_buf);
                 paths.push(relative_path.to_path_buf());
@@ -110,24 +113,145 @@ pub fn find_source_file_paths<'a>(
     }
 }
 
-pub fn mutate_temp_file(temp_file: &std::path::PathBuf, m: &mut Mutant) {
-    let mut contents = String::new();
-    let mut file = File::open(temp_file).expect("File path doesn't seem to work...");
-    file.read_to_string(&mut contents).unwrap();
+/// Walks up from a source file to the nearest directory containing the language's manifest.
+pub fn find_project_root(src_path: &Path, config: &dyn LanguageConfig) -> Result<PathBuf> {
+    let src_path = src_path.canonicalize()?;
 
-    let mut original_bytes = contents.into_bytes();
+    src_path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(config.manifest_name()).is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "No {} found for {}",
+                    config.manifest_name(),
+                    src_path.display()
+                ),
+            )
+        })
+}
 
-    replace_bytes(
-        &mut original_bytes,
-        m.span_start() as usize,
-        token_as_bytes(&m.token()).unwrap(),
-        token_as_bytes(&m.mutation()).unwrap(),
-    );
-    contents = String::from_utf8_lossy(original_bytes.as_slice()).into_owned();
+/// Applies every edit of the mutant to its copy of the source file.
+/// Fails if an edit's span is out of range, overlaps another edit or doesn't point at its token.
+pub fn mutate_temp_file(temp_file: &Path, m: &mut Mutant) -> Result<()> {
+    let mut original_bytes = fs::read(temp_file)?;
 
-    // After modifying the contents, write it back to the temp file
-    let mut file = OpenOptions::new().write(true).open(temp_file).unwrap();
+    let invalid = |message: String| Error::new(ErrorKind::InvalidData, message);
+    let mut previous_end = 0;
+    for edit in m.edits() {
+        let (start, end) = edit.span();
+        if start > end || end as usize > original_bytes.len() {
+            return Err(invalid(format!(
+                "The mutant's span {}..{} is outside of {}",
+                start,
+                end,
+                temp_file.display()
+            )));
+        }
+        if start < previous_end {
+            return Err(invalid(format!(
+                "The mutant's span {}..{} overlaps another of its edits",
+                start, end
+            )));
+        }
+        previous_end = end;
+    }
+
+    // Apply the last edit first so the spans of earlier edits stay valid
+    for edit in m.edits().iter().rev() {
+        let (start, end) = edit.span();
+        let token = token_as_bytes(edit.token()).unwrap_or_default();
+        let original = match edit.mutation() {
+            // The span covers the whole predicate when it is replaced, not just its operator
+            Token::True | Token::False if edit.token().is_relational() => {
+                let predicate = &original_bytes[start as usize..end as usize];
+                if !predicate.windows(token.len()).any(|window| window == token) {
+                    return Err(invalid(format!(
+                        "The mutant's span {}..{} doesn't contain `{}`",
+                        start,
+                        end,
+                        String::from_utf8_lossy(token)
+                    )));
+                }
+                predicate.to_vec()
+            }
+            _ => token.to_vec(),
+        };
+
+        replace_bytes(
+            &mut original_bytes,
+            start as usize,
+            &original,
+            token_as_bytes(edit.mutation()).unwrap_or_default(),
+        )?;
+    }
 
-    // modify string of contents, then write back to temp file
-    file.write_all(contents.as_bytes()).unwrap();
+    fs::write(temp_file, original_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handlers::mutator::mutant_builder, operators::OperatorConfig};
+
+    #[test]
+    fn test_mutate_temp_file_applies_every_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.nr");
+        std::fs::write(&path, "let d = c <= e; let c = a + b;").unwrap();
+
+        let build = |token, span| {
+            mutant_builder(
+                0,
+                token,
+                span,
+                path.clone(),
+                false,
+                None,
+                &OperatorConfig::default(),
+            )
+            .remove(0)
+        };
+        // The first edit shortens the source, so it must be applied last
+        let less_equal = build(Token::LessEqual, (10, 12));
+        let plus = build(Token::Plus, (26, 27));
+        let mut mutant = plus.combine(&less_equal, 1).unwrap();
+
+        mutate_temp_file(&path, &mut mutant).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "let d = c > e; let c = a - b;"
+        );
+    }
+
+    #[test]
+    fn test_mutate_temp_file_stale_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.nr");
+        let build = |span| {
+            mutant_builder(
+                0,
+                Token::Plus,
+                span,
+                path.clone(),
+                false,
+                None,
+                &OperatorConfig::default(),
+            )
+            .remove(0)
+        };
+
+        // The file was shortened after the scan
+        std::fs::write(&path, "a + b").unwrap();
+        let error = mutate_temp_file(&path, &mut build((8, 9))).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // The span no longer points at its token
+        let error = mutate_temp_file(&path, &mut build((0, 1))).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a + b");
+    }
 }
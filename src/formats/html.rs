@@ -0,0 +1,17 @@
+//! A single-file HTML report. The Stryker report, sources included, is embedded
+//! in the page alongside the viewer, so it can be opened offline.
+
+use crate::formats::{stryker, Report};
+use std::io::{Error, Result};
+
+const TEMPLATE: &str = include_str!("report.html");
+const PLACEHOLDER: &str = "/*__REPORT__*/";
+
+pub fn render(report: &Report) -> Result<String> {
+    let json = serde_json::to_string(&stryker::report(report)).map_err(Error::other)?;
+
+    // `<` only ever appears inside JSON strings, so escaping it can't close the script tag early
+    let json = json.replace('<', "\\u003c");
+
+    Ok(TEMPLATE.replace(PLACEHOLDER, &json))
+}
@@ -0,0 +1,212 @@
+//! The JSON report.
+//!
+//! The schema is versioned by `schema_version`; fields are only ever added within a version.
+//! See the "JSON report" section of the README for a description of every field.
+
+use crate::formats::{token_text, Report};
+use crate::handlers::mutator::{MutationStatus, MutationSummary};
+use crate::operators::OperatorFamily;
+use serde::Serialize;
+use std::io::{Error, Result};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    hunter_version: &'static str,
+    language: &'static str,
+    test_runner: &'static str,
+    test_runner_version: Option<String>,
+    command: &'static str,
+    summary: &'a MutationSummary,
+    mutation_score: Option<f64>,
+    mutants: Vec<JsonMutant>,
+}
+
+#[derive(Serialize)]
+struct JsonMutant {
+    id: u32,
+    file: String,
+    line: usize,
+    column: usize,
+    span: JsonSpan,
+    function: Option<String>,
+    item_path: Option<String>,
+    operator: Option<String>,
+    original: String,
+    replacement: String,
+    status: MutationStatus,
+    duration_ms: Option<u128>,
+    killed_by: Option<String>,
+    diagnostic: Option<String>,
+    order: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edits: Option<Vec<JsonEdit>>,
+}
+
+#[derive(Serialize)]
+struct JsonEdit {
+    span: JsonSpan,
+    operator: Option<String>,
+    original: String,
+    replacement: String,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    start: u32,
+    end: u32,
+}
+
+pub fn render(report: &Report) -> Result<String> {
+    let mutants = report
+        .mutants
+        .iter()
+        .map(|mutant| {
+            let (line, column) = report.location(mutant);
+            JsonMutant {
+                id: mutant.id(),
+                file: mutant.path().display().to_string(),
+                line,
+                column,
+                span: JsonSpan {
+                    start: mutant.span_start(),
+                    end: mutant.span_end(),
+                },
+                function: mutant.function().map(str::to_string),
+                item_path: mutant.item_path().map(str::to_string),
+                operator: OperatorFamily::of(&mutant.token()).map(|family| family.to_string()),
+                original: report.original_text(mutant).to_string(),
+                replacement: token_text(&mutant.mutation()),
+                status: mutant.status(),
+                duration_ms: mutant.duration().map(|d| d.as_millis()),
+                killed_by: mutant.killed_by().map(str::to_string),
+                diagnostic: mutant.diagnostic().map(str::to_string),
+                order: mutant.order(),
+                // Only higher-order mutants list their edits, the fields above describe the first
+                edits: (mutant.order() > 1).then(|| {
+                    mutant
+                        .edits()
+                        .iter()
+                        .map(|edit| JsonEdit {
+                            span: JsonSpan {
+                                start: edit.span().0,
+                                end: edit.span().1,
+                            },
+                            operator: OperatorFamily::of(edit.token())
+                                .map(|family| family.to_string()),
+                            original: report.edit_text(mutant, edit).to_string(),
+                            replacement: token_text(edit.mutation()),
+                        })
+                        .collect()
+                }),
+            }
+        })
+        .collect();
+
+    let json_report = JsonReport {
+        schema_version: SCHEMA_VERSION,
+        hunter_version: env!("CARGO_PKG_VERSION"),
+        language: report.config.name(),
+        test_runner: report.config.test_runner(),
+        test_runner_version: report.config.test_runner_version(),
+        command: report.command,
+        summary: &report.summary,
+        mutation_score: report.mutation_score,
+        mutants,
+    };
+
+    serde_json::to_string_pretty(&json_report).map_err(Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::config, formats::fixtures, languages::common::Language};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_render() {
+        let mutants = fixtures::mutants();
+        let config = config(Language::Noir);
+        let report = fixtures::report(&mutants, &*config);
+
+        let mut rendered: Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+        // Depends on the nargo installed, if any
+        rendered["test_runner_version"] = Value::Null;
+
+        let mutant = |id: u32, line: usize, column: usize, span: (u32, u32), operator: &str| {
+            json!({
+                "id": id,
+                "file": "src/main.nr",
+                "line": line,
+                "column": column,
+                "span": { "start": span.0, "end": span.1 },
+                "function": "check",
+                "item_path": "check",
+                "operator": operator,
+                "status": "survived",
+                "duration_ms": null,
+                "killed_by": null,
+                "diagnostic": null,
+                "order": 1
+            })
+        };
+
+        let mut survived = mutant(0, 2, 14, (47, 48), "relational");
+        survived["original"] = json!("<");
+        survived["replacement"] = json!(">=");
+
+        let mut killed = mutant(1, 3, 7, (59, 60), "arithmetic");
+        killed["original"] = json!("+");
+        killed["replacement"] = json!("-");
+        killed["status"] = json!("killed");
+        killed["duration_ms"] = json!(120);
+        killed["killed_by"] = json!("test_check");
+
+        let mut constraint = mutant(2, 2, 5, (38, 52), "constraint");
+        constraint["original"] = json!("assert(a < b);");
+        constraint["replacement"] = json!("");
+
+        let mut second_order = survived.clone();
+        second_order["id"] = json!(3);
+        second_order["order"] = json!(2);
+        second_order["edits"] = json!([
+            {
+                "span": { "start": 47, "end": 48 },
+                "operator": "relational",
+                "original": "<",
+                "replacement": ">="
+            },
+            {
+                "span": { "start": 59, "end": 60 },
+                "operator": "arithmetic",
+                "original": "+",
+                "replacement": "-"
+            }
+        ]);
+
+        assert_eq!(
+            rendered,
+            json!({
+                "schema_version": 1,
+                "hunter_version": env!("CARGO_PKG_VERSION"),
+                "language": "Noir",
+                "test_runner": "nargo",
+                "test_runner_version": null,
+                "command": "mutate",
+                "summary": {
+                    "total": 4,
+                    "pending": 0,
+                    "unbuildable": 0,
+                    "timed_out": 0,
+                    "killed": 1,
+                    "survived": 3
+                },
+                "mutation_score": 25.0,
+                "mutants": [survived, killed, constraint, second_order]
+            })
+        );
+    }
+}
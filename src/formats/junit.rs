@@ -0,0 +1,281 @@
+//! A JUnit XML report, treating each mutant as a test case that passes when the mutant is detected.
+//! Mutants are grouped into one test suite per source file.
+
+use crate::{
+    formats::{line_column, mutated_lines, token_text, Report},
+    handlers::mutator::{Mutant, MutationStatus},
+};
+use std::{collections::BTreeMap, fmt::Write, io::Result, time::Duration};
+
+pub fn render(report: &Report) -> Result<String> {
+    let mut suites: BTreeMap<String, Vec<&Mutant>> = BTreeMap::new();
+    for mutant in report.mutants {
+        suites
+            .entry(mutant.path().display().to_string())
+            .or_default()
+            .push(mutant);
+    }
+
+    let mut body = String::new();
+    for (file, mutants) in &suites {
+        let counts = Counts::of(mutants);
+        let _ = writeln!(
+            body,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{:.3}">"#,
+            escape(file),
+            counts.tests,
+            counts.failures,
+            counts.errors,
+            counts.skipped,
+            counts.time
+        );
+
+        for mutant in mutants {
+            body.push_str(&test_case(report, file, mutant));
+        }
+
+        body.push_str("  </testsuite>\n");
+    }
+
+    let all: Vec<&Mutant> = report.mutants.iter().collect();
+    let counts = Counts::of(&all);
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"Hunter mutation tests\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n{}</testsuites>\n",
+        counts.tests, counts.failures, counts.errors, counts.skipped, counts.time, body
+    ))
+}
+
+fn test_case(report: &Report, file: &str, mutant: &Mutant) -> String {
+    let source = report.source(mutant.path());
+    let (line, column) = line_column(source, mutant.span_start() as usize);
+    let time = mutant.duration().map_or(0.0, |d| d.as_secs_f64());
+
+    let edits = mutant
+        .edits()
+        .iter()
+        .map(|edit| {
+            format!(
+                "`{}` -> `{}`",
+                report.edit_text(mutant, edit),
+                token_text(edit.mutation())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let name = format!(
+        "mutant #{} at line {}:{}: {}",
+        mutant.id(),
+        line,
+        column,
+        edits
+    );
+
+    // Every edit of a higher-order mutant is in the diff, across as many lines as they span
+    let (before, after) = mutated_lines(source, mutant);
+    let prefixed = |text: &str, prefix: char| {
+        text.split('\n')
+            .map(|line| format!("{}{}", prefix, line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let diff = format!(
+        "--- {file}:{line}\n+++ {file}:{line}\n{before}\n{after}",
+        file = file,
+        line = line,
+        before = prefixed(&before, '-'),
+        after = prefixed(&after, '+')
+    );
+
+    let outcome = match mutant.status() {
+        MutationStatus::Survived => format!(
+            "      <failure message=\"{}\" type=\"SurvivedMutant\">{}</failure>\n",
+            escape(&format!(
+                "Mutant survived: {} did not cause any test to fail",
+                report.changes(mutant)
+            )),
+            escape(&diff)
+        ),
+        MutationStatus::Timeout => format!(
+            "      <error message=\"{}\" type=\"Timeout\">{}</error>\n",
+            escape("The test suite timed out against this mutant"),
+            escape(&diff)
+        ),
+        MutationStatus::Unbuildable => format!(
+            "      <skipped message=\"{}\"/>\n",
+            escape(&format!(
+                "Mutant does not compile: {}",
+                mutant.diagnostic().unwrap_or_default()
+            ))
+        ),
+        MutationStatus::Pending => {
+            "      <skipped message=\"Mutant has not been tested\"/>\n".to_string()
+        }
+        MutationStatus::Killed => String::new(),
+    };
+
+    let system_out = match mutant.killed_by() {
+        Some(test) => format!(
+            "      <system-out>{}</system-out>\n",
+            escape(&format!("Killed by {}", test))
+        ),
+        None => String::new(),
+    };
+
+    if outcome.is_empty() && system_out.is_empty() {
+        format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"/>\n",
+            escape(&name),
+            escape(file),
+            time
+        )
+    } else {
+        format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n{}{}    </testcase>\n",
+            escape(&name),
+            escape(file),
+            time,
+            outcome,
+            system_out
+        )
+    }
+}
+
+struct Counts {
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+    time: f64,
+}
+
+impl Counts {
+    fn of(mutants: &[&Mutant]) -> Counts {
+        let count =
+            |status: MutationStatus| mutants.iter().filter(|m| m.status() == status).count();
+        Counts {
+            tests: mutants.len(),
+            failures: count(MutationStatus::Survived),
+            errors: count(MutationStatus::Timeout),
+            skipped: count(MutationStatus::Unbuildable) + count(MutationStatus::Pending),
+            // Summed as a `Duration`, since an empty `f64` sum is `-0.0`
+            time: mutants
+                .iter()
+                .filter_map(|m| m.duration())
+                .sum::<Duration>()
+                .as_secs_f64(),
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace aren't allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::config, formats::fixtures, handlers::mutator::mutant_builder,
+        languages::common::Language, operators::OperatorConfig, token::Token,
+    };
+    use std::path::PathBuf;
+
+    const LIB: &str = "src/lib.nr";
+
+    #[test]
+    fn test_render() {
+        // A second file, with a timed out and an unbuildable mutant
+        let mut mutants = fixtures::mutants();
+        let lib_mutant = |id: u32, status: MutationStatus| {
+            let mut mutant = mutant_builder(
+                id,
+                Token::Plus,
+                (26, 27),
+                PathBuf::from(LIB),
+                false,
+                None,
+                &OperatorConfig::default(),
+            )
+            .remove(0);
+            mutant.set_status(status);
+            mutant
+        };
+        let mut unbuildable = lib_mutant(5, MutationStatus::Unbuildable);
+        unbuildable.set_diagnostic("error: expected u8".to_string());
+        mutants.extend([lib_mutant(4, MutationStatus::Timeout), unbuildable]);
+
+        let config = config(Language::Noir);
+        let mut report = fixtures::report(&mutants, &*config);
+        report.sources.insert(
+            PathBuf::from(LIB),
+            "fn f(a: u8) -> u8 {\n    a + 1\n}\n".to_string(),
+        );
+
+        assert_eq!(
+            render(&report).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="Hunter mutation tests" tests="6" failures="3" errors="1" skipped="1" time="0.120">
+  <testsuite name="src/lib.nr" tests="2" failures="0" errors="1" skipped="1" time="0.000">
+    <testcase name="mutant #4 at line 2:7: `+` -&gt; `-`" classname="src/lib.nr" time="0.000">
+      <error message="The test suite timed out against this mutant" type="Timeout">--- src/lib.nr:2
++++ src/lib.nr:2
+-    a + 1
++    a - 1</error>
+    </testcase>
+    <testcase name="mutant #5 at line 2:7: `+` -&gt; `-`" classname="src/lib.nr" time="0.000">
+      <skipped message="Mutant does not compile: error: expected u8"/>
+    </testcase>
+  </testsuite>
+  <testsuite name="src/main.nr" tests="4" failures="3" errors="0" skipped="0" time="0.120">
+    <testcase name="mutant #0 at line 2:14: `&lt;` -&gt; `&gt;=`" classname="src/main.nr" time="0.000">
+      <failure message="Mutant survived: replacing `&lt;` with `&gt;=` did not cause any test to fail" type="SurvivedMutant">--- src/main.nr:2
++++ src/main.nr:2
+-    assert(a &lt; b);
++    assert(a &gt;= b);</failure>
+    </testcase>
+    <testcase name="mutant #1 at line 3:7: `+` -&gt; `-`" classname="src/main.nr" time="0.120">
+      <system-out>Killed by test_check</system-out>
+    </testcase>
+    <testcase name="mutant #2 at line 2:5: `assert(a &lt; b);` -&gt; ``" classname="src/main.nr" time="0.000">
+      <failure message="Mutant survived: deleting `assert(a &lt; b);` did not cause any test to fail" type="SurvivedMutant">--- src/main.nr:2
++++ src/main.nr:2
+-    assert(a &lt; b);
++    </failure>
+    </testcase>
+    <testcase name="mutant #3 at line 2:14: `&lt;` -&gt; `&gt;=`, `+` -&gt; `-`" classname="src/main.nr" time="0.000">
+      <failure message="Mutant survived: replacing `&lt;` with `&gt;=` and replacing `+` with `-` did not cause any test to fail" type="SurvivedMutant">--- src/main.nr:2
++++ src/main.nr:2
+-    assert(a &lt; b);
+-    a + b
++    assert(a &gt;= b);
++    a - b</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"assert(a < b && c > "d");"#),
+            "assert(a &lt; b &amp;&amp; c &gt; &quot;d&quot;);"
+        );
+        assert_eq!(escape("a\u{1b}[31mb"), "a[31mb");
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{
+    cli::Args,
+    config::LanguageConfig,
+    handlers::mutator::{Edit, Mutant, MutationSummary},
+    token::{token_as_bytes, Token},
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Result, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod sarif;
+pub mod stryker;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+// @extendable: add a new variant here to support a new report format
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Stryker,
+    Html,
+    Sarif,
+    Junit,
+}
+
+impl OutputFormat {
+    pub fn list() -> String {
+        ["text", "json", "stryker", "html", "sarif", "junit"].join(", ")
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "stryker" => Ok(OutputFormat::Stryker),
+            "html" => Ok(OutputFormat::Html),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "junit" => Ok(OutputFormat::Junit),
+            _ => Err(format!(
+                "No matching report format. Current supported formats are: {}",
+                OutputFormat::list()
+            )),
+        }
+    }
+}
+
+/// Everything a report writer needs to know about a scan or mutate run
+pub struct Report<'a> {
+    pub command: &'static str,
+    pub mutants: &'a [Mutant],
+    pub config: &'a dyn LanguageConfig,
+    pub summary: MutationSummary,
+    /// `None` for a scan, where no tests have been run yet
+    pub mutation_score: Option<f64>,
+    sources: HashMap<PathBuf, String>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(
+        command: &'static str,
+        mutants: &'a [Mutant],
+        config: &'a dyn LanguageConfig,
+        args: &Args,
+    ) -> Report<'a> {
+        let summary = MutationSummary::from_mutants(mutants);
+        let mutation_score = match command {
+            "scan" => None,
+            _ => Some(summary.score(!args.exclude_unbuildable, !args.exclude_timeouts)),
+        };
+
+        let mut sources = HashMap::new();
+        for mutant in mutants {
+            sources
+                .entry(mutant.path().to_path_buf())
+                .or_insert_with(|| fs::read_to_string(mutant.path()).unwrap_or_default());
+        }
+
+        Report {
+            command,
+            mutants,
+            config,
+            summary,
+            mutation_score,
+            sources,
+        }
+    }
+
+    pub fn source(&self, path: &Path) -> &str {
+        self.sources.get(path).map_or("", String::as_str)
+    }
+
+    pub fn sources(&self) -> &HashMap<PathBuf, String> {
+        &self.sources
+    }
+
+    /// The 1-based line and column of the start of the mutant's span
+    pub fn location(&self, mutant: &Mutant) -> (usize, usize) {
+        line_column(self.source(mutant.path()), mutant.span_start() as usize)
+    }
+
+    /// The source text the mutant replaces, i.e: `<` or, for a whole predicate, `a < b`
+    pub fn original_text(&self, mutant: &Mutant) -> &str {
+        self.edit_text(mutant, &mutant.edits()[0])
+    }
+
+    /// The source text one of the mutant's edits replaces
+    pub fn edit_text(&self, mutant: &Mutant, edit: &Edit) -> &str {
+        let source = self.source(mutant.path());
+        let (start, end) = edit.span();
+        let end = (end as usize).min(source.len());
+        source.get(start as usize..end).unwrap_or("")
+    }
+
+    /// What the mutant changes, one clause per edit,
+    /// i.e: "replacing `<` with `>=` and deleting `assert(a < b);`"
+    pub fn changes(&self, mutant: &Mutant) -> String {
+        mutant
+            .edits()
+            .iter()
+            .map(|edit| match edit.mutation() {
+                Token::Void => format!("deleting `{}`", self.edit_text(mutant, edit)),
+                mutation => format!(
+                    "replacing `{}` with `{}`",
+                    self.edit_text(mutant, edit),
+                    token_text(mutation)
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join(" and ")
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column, counting columns in characters
+pub fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(contents.len());
+    let before = &contents.as_bytes()[..offset];
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// The source lines containing every edit of the mutant, before and after the edits are applied
+pub fn mutated_lines(source: &str, mutant: &Mutant) -> (String, String) {
+    let edits = mutant.edits();
+    let start = (edits[0].span().0 as usize).min(source.len());
+    let end = (edits[edits.len() - 1].span().1 as usize).clamp(start, source.len());
+
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[end..].find('\n').map_or(source.len(), |i| end + i);
+
+    (
+        source[line_start..line_end].to_string(),
+        apply_edits(source, mutant, line_start..line_end),
+    )
+}
+
+/// The `range` of the source with the mutant's edits in it applied
+pub fn apply_edits(source: &str, mutant: &Mutant, range: Range<usize>) -> String {
+    let mut text = String::new();
+    let mut position = range.start;
+    for edit in mutant.edits() {
+        let (start, end) = (edit.span().0 as usize, edit.span().1 as usize);
+        if start < position || end > range.end {
+            continue;
+        }
+        text.push_str(&source[position..start]);
+        text.push_str(&token_text(edit.mutation()));
+        position = end;
+    }
+    text.push_str(&source[position..range.end]);
+    text
+}
+
+/// The source text of a token, i.e: `==`
+pub fn token_text(token: &Token) -> String {
+    String::from_utf8_lossy(token_as_bytes(token).unwrap_or_default()).into_owned()
+}
+
+/// The name of the mutation operator a mutant belongs to, following Stryker's naming
+pub fn mutator_name(mutant: &Mutant) -> &'static str {
+    match mutant.token() {
+        Token::True | Token::False => return "BooleanLiteral",
+        Token::Condition(_) => return "ConditionalExpression",
+        Token::Constraint(_) => return "ConstraintRemoval",
+        Token::Statement(_) => return "StatementDeletion",
+        Token::ReturnValue(..) => return "ReturnValue",
+        Token::Cast(_) | Token::CastOperand(_) => return "CastWidth",
+        Token::DotDot | Token::DotDotEqual | Token::RangeBound(_) => return "RangeBound",
+        Token::Hint(..) => return "HintTampering",
+        Token::Swap(..) => return "OperandSwap",
+        Token::Index(_) => return "ArrayIndex",
+        Token::FieldAccess(..) => return "FieldSwap",
+        _ => {}
+    }
+    if matches!(mutant.mutation(), Token::True | Token::False) {
+        return "ConditionalExpression";
+    }
+
+    match mutant.token() {
+        Token::Equal | Token::NotEqual => "EqualityOperator",
+        Token::Less | Token::LessEqual | Token::Greater | Token::GreaterEqual => {
+            "RelationalOperator"
+        }
+        Token::Plus | Token::Minus | Token::Star | Token::Slash | Token::Percent => {
+            "ArithmeticOperator"
+        }
+        Token::Ampersand | Token::Pipe | Token::Caret | Token::ShiftLeft | Token::ShiftRight => {
+            "BitwiseOperator"
+        }
+        Token::Increment | Token::Decrement => "UpdateOperator",
+        Token::PlusEquals
+        | Token::MinusEquals
+        | Token::StarEquals
+        | Token::SlashEquals
+        | Token::PercentEquals
+        | Token::AmpersandEquals
+        | Token::PipeEquals
+        | Token::CaretEquals
+        | Token::ShiftLeftEquals
+        | Token::ShiftRightEquals => "AssignmentOperator",
+        Token::DoublePipe | Token::DoubleAmpersand => "LogicalOperator",
+        Token::Bang => "UnaryOperator",
+        Token::Literal(_) => "NumericLiteral",
+        Token::True
+        | Token::False
+        | Token::Condition(_)
+        | Token::Constraint(_)
+        | Token::Statement(_)
+        | Token::ReturnValue(..)
+        | Token::Cast(_)
+        | Token::CastOperand(_)
+        | Token::DotDot
+        | Token::DotDotEqual
+        | Token::RangeBound(_)
+        | Token::Hint(..)
+        | Token::Swap(..)
+        | Token::Index(_)
+        | Token::FieldAccess(..)
+        | Token::Void => "Unknown",
+    }
+}
+
+/// Writes the report to `output_path`, replacing any existing file, or to stdout
+pub fn write_report(
+    format: &OutputFormat,
+    report: &Report,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let contents = match format {
+        OutputFormat::Text => return Ok(()),
+        OutputFormat::Json => json::render(report)?,
+        OutputFormat::Stryker => stryker::render(report)?,
+        OutputFormat::Html => html::render(report)?,
+        OutputFormat::Sarif => sarif::render(report)?,
+        OutputFormat::Junit => junit::render(report)?,
+    };
+
+    match output_path {
+        Some(path) => {
+            let mut file = File::create(path)?;
+            file.write_all(contents.as_bytes())
+        }
+        None => {
+            let mut stdout = io::stdout();
+            writeln!(stdout, "{}", contents)
+        }
+    }
+}
+
+/// A mutate run over one small file, shared by the report format tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::{
+        handlers::mutator::{mutant_builder, MutationStatus},
+        operators::{OperatorConfig, OperatorFamily},
+        token::MutationSet,
+    };
+    use std::time::Duration;
+
+    pub const PATH: &str = "src/main.nr";
+    pub const SOURCE: &str =
+        "fn check(a: u32, b: u32) -> u32 {\n    assert(a < b);\n    a + b\n}\n";
+
+    fn mutant(id: u32, original: Token, span: (u32, u32), status: MutationStatus) -> Mutant {
+        let operators = OperatorConfig::new(MutationSet::Minimal, OperatorFamily::all(), vec![]);
+        let mut mutant = mutant_builder(
+            id,
+            original,
+            span,
+            PathBuf::from(PATH),
+            false,
+            None,
+            &operators,
+        )
+        .remove(0);
+        mutant.set_status(status);
+        mutant.set_item(Some("check".to_string()), Some("check".to_string()));
+        mutant
+    }
+
+    /// A surviving `<`, a killed `+`, a deleted constraint and the second-order mutant
+    /// combining the first two
+    pub fn mutants() -> Vec<Mutant> {
+        let survived = mutant(0, Token::Less, (47, 48), MutationStatus::Survived);
+        let mut killed = mutant(1, Token::Plus, (59, 60), MutationStatus::Killed);
+        killed.set_killed_by("test_check".to_string());
+        killed.set_duration(Duration::from_millis(120));
+        let constraint = mutant(
+            2,
+            Token::Constraint("assert(a < b);".to_string()),
+            (38, 52),
+            MutationStatus::Survived,
+        );
+        let mut second_order = survived.combine(&killed, 3).unwrap();
+        second_order.set_status(MutationStatus::Survived);
+        vec![survived, killed, constraint, second_order]
+    }
+
+    pub fn report<'a>(mutants: &'a [Mutant], config: &'a dyn LanguageConfig) -> Report<'a> {
+        let summary = MutationSummary::from_mutants(mutants);
+        Report {
+            command: "mutate",
+            mutants,
+            config,
+            mutation_score: Some(summary.score(true, true)),
+            summary,
+            sources: HashMap::from([(PathBuf::from(PATH), SOURCE.to_string())]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_column() {
+        let contents = "fn main() {\n    assert(x == y);\n}\n";
+        assert_eq!(line_column(contents, 0), (1, 1));
+        assert_eq!(line_column(contents, 3), (1, 4));
+        assert_eq!(line_column(contents, 23), (2, 12));
+        assert_eq!(line_column(contents, 1000), (4, 1));
+    }
+
+    #[test]
+    fn test_mutated_lines() {
+        let mutants = fixtures::mutants();
+        let source = fixtures::SOURCE;
+        assert_eq!(
+            mutated_lines(source, &mutants[0]),
+            (
+                "    assert(a < b);".to_string(),
+                "    assert(a >= b);".to_string()
+            )
+        );
+        assert_eq!(
+            mutated_lines(source, &mutants[2]),
+            ("    assert(a < b);".to_string(), "    ".to_string())
+        );
+        // Both edits of the second-order mutant, on their own lines
+        assert_eq!(
+            mutated_lines(source, &mutants[3]),
+            (
+                "    assert(a < b);\n    a + b".to_string(),
+                "    assert(a >= b);\n    a - b".to_string()
+            )
+        );
+        assert_eq!(apply_edits(source, &mutants[3], 47..60), ">= b);\n    a -");
+    }
+}
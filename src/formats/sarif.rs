@@ -0,0 +1,363 @@
+//! A SARIF 2.1.0 log of the surviving mutants, for code-scanning integrations.
+//! Each survivor is reported as a warning on the exact source regions that were mutated.
+
+use crate::{
+    formats::{line_column, token_text, Report},
+    handlers::mutator::{Edit, Mutant, MutationStatus},
+    operators::OperatorFamily,
+    token::Token,
+};
+use serde::Serialize;
+use std::io::{Error, Result};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://github.com/nfurfaro/hunter";
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    short_description: Message,
+    full_description: Message,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logical_locations: Vec<LogicalLocation>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LogicalLocation {
+    name: String,
+    fully_qualified_name: String,
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    uri_base_id: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+pub fn render(report: &Report) -> Result<String> {
+    let mut rules: Vec<Rule> = vec![];
+    let mut results: Vec<SarifResult> = vec![];
+
+    for mutant in report
+        .mutants
+        .iter()
+        .filter(|m| m.status() == MutationStatus::Survived)
+    {
+        // The same names as the JSON report's `operator` and `--operators`
+        let rule_id = OperatorFamily::of(&mutant.token())
+            .map_or_else(|| "unknown".to_string(), |family| family.to_string());
+        let rule_index = match rules.iter().position(|rule| rule.id == rule_id) {
+            Some(index) => index,
+            None => {
+                rules.push(rule(&rule_id));
+                rules.len() - 1
+            }
+        };
+
+        let logical_locations: Vec<LogicalLocation> = mutant
+            .function()
+            .zip(mutant.item_path())
+            .map(|(name, path)| LogicalLocation {
+                name: name.to_string(),
+                fully_qualified_name: path.to_string(),
+                kind: "function",
+            })
+            .into_iter()
+            .collect();
+
+        results.push(SarifResult {
+            rule_id,
+            rule_index,
+            level: "warning",
+            message: Message {
+                text: message(report, mutant),
+            },
+            // A higher-order mutant is located at every one of its edits
+            locations: mutant
+                .edits()
+                .iter()
+                .map(|edit| Location {
+                    physical_location: physical_location(report, mutant, edit),
+                    logical_locations: logical_locations.clone(),
+                })
+                .collect(),
+        });
+    }
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "Hunter",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: INFORMATION_URI,
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+
+    serde_json::to_string_pretty(&log).map_err(Error::other)
+}
+
+/// What the surviving mutant changed, describing every edit of a higher-order mutant
+fn message(report: &Report, mutant: &Mutant) -> String {
+    if mutant.order() == 1 {
+        match mutant.token() {
+            Token::Constraint(_) => {
+                return format!(
+                    "Potentially under-constrained: deleting `{}` did not cause any test to fail.",
+                    report.original_text(mutant)
+                )
+            }
+            Token::Hint(..) => {
+                return format!(
+                    "Unverified hint: replacing the result of `{}` with `{}` did not cause any test to fail.",
+                    report.original_text(mutant),
+                    token_text(&mutant.mutation())
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let changes = report.changes(mutant);
+    match mutant.order() {
+        1 => format!(
+            "Surviving mutant: {} did not cause any test to fail.",
+            changes
+        ),
+        order => format!(
+            "Surviving order {} mutant: {} did not cause any test to fail.",
+            order, changes
+        ),
+    }
+}
+
+fn physical_location(report: &Report, mutant: &Mutant, edit: &Edit) -> PhysicalLocation {
+    let source = report.source(mutant.path());
+    let (start, end) = edit.span();
+    let (start_line, start_column) = line_column(source, start as usize);
+    let (end_line, end_column) = line_column(source, end as usize);
+
+    PhysicalLocation {
+        artifact_location: ArtifactLocation {
+            uri: artifact_uri(&mutant.path().to_string_lossy()),
+            uri_base_id: "%SRCROOT%",
+        },
+        region: Region {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            byte_offset: start,
+            byte_length: end - start,
+        },
+    }
+}
+
+fn rule(id: &str) -> Rule {
+    Rule {
+        id: id.to_string(),
+        short_description: Message {
+            text: format!("Surviving {} mutant", id),
+        },
+        full_description: Message {
+            text: format!(
+                "A {} mutation was applied to this code and the test suite still passed. \
+                 The tests don't verify the behaviour of this expression.",
+                id
+            ),
+        },
+        default_configuration: Configuration { level: "warning" },
+    }
+}
+
+/// SARIF URIs use forward slashes and are relative to the source root
+fn artifact_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::config, formats::fixtures, languages::common::Language};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_render() {
+        let mutants = fixtures::mutants();
+        let config = config(Language::Noir);
+        let report = fixtures::report(&mutants, &*config);
+
+        let rendered: Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+
+        let rule = |id: &str| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": format!("Surviving {} mutant", id) },
+                "fullDescription": {
+                    "text": format!(
+                        "A {} mutation was applied to this code and the test suite still passed. \
+                         The tests don't verify the behaviour of this expression.",
+                        id
+                    )
+                },
+                "defaultConfiguration": { "level": "warning" }
+            })
+        };
+        let location = |line: usize, start: usize, end: usize, offset: u32, length: u32| {
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "src/main.nr", "uriBaseId": "%SRCROOT%" },
+                    "region": {
+                        "startLine": line,
+                        "startColumn": start,
+                        "endLine": line,
+                        "endColumn": end,
+                        "byteOffset": offset,
+                        "byteLength": length
+                    }
+                },
+                "logicalLocations": [
+                    { "name": "check", "fullyQualifiedName": "check", "kind": "function" }
+                ]
+            })
+        };
+        let survivor = json!({
+            "ruleId": "relational",
+            "ruleIndex": 0,
+            "level": "warning",
+            "message": {
+                "text": "Surviving mutant: replacing `<` with `>=` did not cause any test to fail."
+            },
+            "locations": [location(2, 14, 15, 47, 1)]
+        });
+
+        assert_eq!(
+            rendered,
+            json!({
+                "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                "version": "2.1.0",
+                "runs": [{
+                    "tool": {
+                        "driver": {
+                            "name": "Hunter",
+                            "version": env!("CARGO_PKG_VERSION"),
+                            "informationUri": "https://github.com/nfurfaro/hunter",
+                            "rules": [rule("relational"), rule("constraint")]
+                        }
+                    },
+                    "results": [
+                        survivor,
+                        {
+                            "ruleId": "constraint",
+                            "ruleIndex": 1,
+                            "level": "warning",
+                            "message": {
+                                "text": "Potentially under-constrained: deleting `assert(a < b);` \
+                                         did not cause any test to fail."
+                            },
+                            "locations": [location(2, 5, 19, 38, 14)]
+                        },
+                        {
+                            "ruleId": "relational",
+                            "ruleIndex": 0,
+                            "level": "warning",
+                            "message": {
+                                "text": "Surviving order 2 mutant: replacing `<` with `>=` and \
+                                         replacing `+` with `-` did not cause any test to fail."
+                            },
+                            "locations": [
+                                location(2, 14, 15, 47, 1),
+                                location(3, 7, 8, 59, 1)
+                            ]
+                        }
+                    ]
+                }]
+            })
+        );
+    }
+}
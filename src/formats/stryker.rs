@@ -0,0 +1,226 @@
+//! The Stryker `mutation-testing-report-schema` report, as consumed by the
+//! mutation-testing-elements viewers and dashboards.
+
+use crate::{
+    formats::{apply_edits, line_column, mutator_name, Report},
+    handlers::mutator::{Mutant, MutationStatus},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    io::{Error, Result},
+};
+
+pub const SCHEMA_VERSION: &str = "1";
+const THRESHOLD_HIGH: u32 = 80;
+const THRESHOLD_LOW: u32 = 60;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrykerReport {
+    schema_version: &'static str,
+    thresholds: Thresholds,
+    project_root: String,
+    files: BTreeMap<String, FileResult>,
+    framework: Framework,
+}
+
+#[derive(Serialize)]
+struct Thresholds {
+    high: u32,
+    low: u32,
+}
+
+#[derive(Serialize)]
+struct Framework {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct FileResult {
+    language: String,
+    source: String,
+    mutants: Vec<MutantResult>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MutantResult {
+    id: String,
+    mutator_name: &'static str,
+    replacement: String,
+    location: Location,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u128>,
+}
+
+#[derive(Serialize)]
+struct Location {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+pub fn report(report: &Report) -> StrykerReport {
+    let mut files: BTreeMap<String, FileResult> = BTreeMap::new();
+
+    for mutant in report.mutants {
+        let source = report.source(mutant.path());
+        let file = files
+            .entry(mutant.path().display().to_string())
+            .or_insert_with(|| FileResult {
+                language: report.config.name().to_lowercase(),
+                source: source.to_string(),
+                mutants: vec![],
+            });
+
+        // A higher-order mutant is located from its first edit to its last, and the
+        // replacement is that whole region with every edit applied
+        let edits = mutant.edits();
+        let start = (edits[0].span().0 as usize).min(source.len());
+        let end = (edits[edits.len() - 1].span().1 as usize).clamp(start, source.len());
+        let (start_line, start_column) = line_column(source, start);
+        let (end_line, end_column) = line_column(source, end);
+
+        file.mutants.push(MutantResult {
+            id: mutant.id().to_string(),
+            mutator_name: mutator_name(mutant),
+            replacement: apply_edits(source, mutant, start..end),
+            location: Location {
+                start: Position {
+                    line: start_line,
+                    column: start_column,
+                },
+                end: Position {
+                    line: end_line,
+                    column: end_column,
+                },
+            },
+            status: status(mutant),
+            status_reason: status_reason(mutant),
+            duration: mutant.duration().map(|d| d.as_millis()),
+        });
+    }
+
+    StrykerReport {
+        schema_version: SCHEMA_VERSION,
+        thresholds: Thresholds {
+            high: THRESHOLD_HIGH,
+            low: THRESHOLD_LOW,
+        },
+        project_root: std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default(),
+        files,
+        framework: Framework {
+            name: "Hunter",
+            version: env!("CARGO_PKG_VERSION"),
+        },
+    }
+}
+
+pub fn render(report: &Report) -> Result<String> {
+    serde_json::to_string_pretty(&self::report(report)).map_err(Error::other)
+}
+
+fn status(mutant: &Mutant) -> &'static str {
+    match mutant.status() {
+        MutationStatus::Pending => "Pending",
+        MutationStatus::Survived => "Survived",
+        MutationStatus::Killed => "Killed",
+        MutationStatus::Unbuildable => "CompileError",
+        MutationStatus::Timeout => "Timeout",
+    }
+}
+
+fn status_reason(mutant: &Mutant) -> Option<String> {
+    match mutant.status() {
+        MutationStatus::Killed => mutant.killed_by().map(|test| format!("Killed by {}", test)),
+        MutationStatus::Unbuildable => mutant.diagnostic().map(str::to_string),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::config, formats::fixtures, languages::common::Language};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_render() {
+        let mutants = fixtures::mutants();
+        let config = config(Language::Noir);
+        let report = fixtures::report(&mutants, &*config);
+
+        let rendered: Value = serde_json::from_str(&render(&report).unwrap()).unwrap();
+
+        let location = |line: usize, start: usize, end: usize| {
+            json!({
+                "start": { "line": line, "column": start },
+                "end": { "line": line, "column": end }
+            })
+        };
+
+        assert_eq!(
+            rendered,
+            json!({
+                "schemaVersion": "1",
+                "thresholds": { "high": 80, "low": 60 },
+                "projectRoot": std::env::current_dir().unwrap().display().to_string(),
+                "files": {
+                    "src/main.nr": {
+                        "language": "noir",
+                        "source": fixtures::SOURCE,
+                        "mutants": [
+                            {
+                                "id": "0",
+                                "mutatorName": "RelationalOperator",
+                                "replacement": ">=",
+                                "location": location(2, 14, 15),
+                                "status": "Survived"
+                            },
+                            {
+                                "id": "1",
+                                "mutatorName": "ArithmeticOperator",
+                                "replacement": "-",
+                                "location": location(3, 7, 8),
+                                "status": "Killed",
+                                "statusReason": "Killed by test_check",
+                                "duration": 120
+                            },
+                            {
+                                "id": "2",
+                                "mutatorName": "ConstraintRemoval",
+                                "replacement": "",
+                                "location": location(2, 5, 19),
+                                "status": "Survived"
+                            },
+                            {
+                                "id": "3",
+                                "mutatorName": "RelationalOperator",
+                                // From the first edit to the last, with both applied
+                                "replacement": ">= b);\n    a -",
+                                "location": {
+                                    "start": { "line": 2, "column": 14 },
+                                    "end": { "line": 3, "column": 8 }
+                                },
+                                "status": "Survived"
+                            }
+                        ]
+                    }
+                },
+                "framework": { "name": "Hunter", "version": env!("CARGO_PKG_VERSION") }
+            })
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    config::LanguageConfig, file_manager::find_project_root, handlers::mutator::Mutant,
+    reporter::progress, workspace::Workspace,
+};
+use colored::*;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    io::{Error, Result},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
+
+/// Timings of the unmutated projects, measured in the same sandbox layout the mutants run in.
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    projects: BTreeMap<PathBuf, ProjectBaseline>,
+}
+
+/// Timings of a single unmutated project
+#[derive(Debug, Clone, Default)]
+pub struct ProjectBaseline {
+    build_duration: Duration,
+    test_duration: Duration,
+    test_durations: Vec<(String, Duration)>,
+}
+
+impl ProjectBaseline {
+    /// Wall time of building the project
+    pub fn build_duration(&self) -> Duration {
+        self.build_duration
+    }
+
+    /// Wall time of running the project's full test suite
+    pub fn test_duration(&self) -> Duration {
+        self.test_duration
+    }
+
+    /// Wall time of each test, as reported by the test runner
+    pub fn test_durations(&self) -> &[(String, Duration)] {
+        &self.test_durations
+    }
+
+    /// The expected cost of building and testing a mutant of this project
+    pub fn mutant_duration(&self) -> Duration {
+        self.build_duration + self.test_duration
+    }
+}
+
+impl Baseline {
+    /// Wall time of building every project the mutants belong to
+    pub fn build_duration(&self) -> Duration {
+        self.projects.values().map(|p| p.build_duration).sum()
+    }
+
+    /// Wall time of running every project's full test suite
+    pub fn test_duration(&self) -> Duration {
+        self.projects.values().map(|p| p.test_duration).sum()
+    }
+
+    /// Wall time of every project's tests, slowest first
+    pub fn test_durations(&self) -> Vec<&(String, Duration)> {
+        let mut durations: Vec<&(String, Duration)> = self
+            .projects
+            .values()
+            .flat_map(|p| &p.test_durations)
+            .collect();
+        durations.sort_by_key(|(_, duration)| Reverse(*duration));
+        durations
+    }
+
+    /// The timings of the project at `project_root`
+    pub fn project(&self, project_root: &Path) -> Option<&ProjectBaseline> {
+        self.projects.get(project_root)
+    }
+
+    /// The expected cost of building and testing the mutant, in its own project
+    pub fn mutant_duration(&self, mutant: &Mutant, config: &dyn LanguageConfig) -> Duration {
+        find_project_root(mutant.path(), config)
+            .ok()
+            .and_then(|project_root| self.project(&project_root))
+            .map_or(Duration::ZERO, ProjectBaseline::mutant_duration)
+    }
+}
+
+/// Builds and tests the untouched projects, failing if they aren't green.
+/// Without this, a broken project would report every mutant as killed.
+pub fn baseline(mutants: &[Mutant], config: &dyn LanguageConfig) -> Result<Baseline> {
+    progress("Verifying the unmutated test suite...".green());
+
+    let project_roots = mutants
+        .iter()
+        .map(|m| find_project_root(m.path(), config))
+        .collect::<Result<BTreeSet<PathBuf>>>()?;
+
+    let mut baseline = Baseline::default();
+    let mut workspace = Workspace::new(config).map_err(Error::other)?;
+
+    for project_root in project_roots {
+        workspace.load_project(config, &project_root)?;
+        let mut project = ProjectBaseline::default();
+
+        let start = Instant::now();
+        let build_output = config
+            .build_mutant_project(workspace.path(), None)
+            .expect("The baseline runs without a timeout");
+        project.build_duration = start.elapsed();
+
+        if !build_output.status.success() {
+            return Err(Error::other(format!(
+                "{}\n{}",
+                format!(
+                    "The unmutated project at {} does not build. Fix the build before running Hunter:",
+                    project_root.display()
+                )
+                .red(),
+                output_text(&build_output)
+            )));
+        }
+
+        let start = Instant::now();
+        let test_output = config
+            .test_mutant_project(workspace.path(), None)
+            .expect("The baseline runs without a timeout");
+        project.test_duration = start.elapsed();
+
+        let outcomes = test_outcomes(&test_output, config);
+        if !test_output.status.success() {
+            let failing: Vec<&str> = outcomes
+                .iter()
+                .filter(|(_, passed)| !passed)
+                .map(|(name, _)| name.as_str())
+                .collect();
+
+            return Err(Error::other(format!(
+                "{}\n{}\n{}",
+                format!(
+                    "The unmutated test suite fails for the project at {}. All tests must pass before running Hunter.",
+                    project_root.display()
+                )
+                .red(),
+                format!("Failing tests: {}", failing.join(", ")).yellow(),
+                output_text(&test_output)
+            )));
+        }
+
+        project.test_durations = test_durations(&test_output, config, project.test_duration);
+        baseline.projects.insert(project_root, project);
+    }
+
+    progress(
+        format!(
+            "Baseline passed: build {:.2?}, tests {:.2?}",
+            baseline.build_duration(),
+            baseline.test_duration()
+        )
+        .cyan(),
+    );
+
+    Ok(baseline)
+}
+
+/// The name and pass/fail outcome of each test reported by the test runner
+pub fn test_outcomes(output: &process::Output, config: &dyn LanguageConfig) -> Vec<(String, bool)> {
+    let output = output_text(output);
+    config
+        .test_outcome_regex()
+        .captures_iter(&output)
+        .map(|c| (c[1].to_string(), &c[2] == "ok"))
+        .collect()
+}
+
+/// How long each test reported by the test runner took. A test whose duration isn't printed is
+/// given the wall time of the whole suite, `suite_duration`, which it can't have exceeded.
+pub fn test_durations(
+    output: &process::Output,
+    config: &dyn LanguageConfig,
+    suite_duration: Duration,
+) -> Vec<(String, Duration)> {
+    let output = output_text(output);
+    config
+        .test_outcome_regex()
+        .captures_iter(&output)
+        .map(|c| {
+            let duration = c.get(3).zip(c.get(4)).and_then(|(value, unit)| {
+                let value: f64 = value.as_str().parse().ok()?;
+                Some(match unit.as_str() {
+                    "ms" => Duration::from_secs_f64(value / 1000.0),
+                    _ => Duration::from_secs_f64(value),
+                })
+            });
+            (c[1].to_string(), duration.unwrap_or(suite_duration))
+        })
+        .collect()
+}
+
+fn output_text(output: &process::Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::config, handlers::mutator::mutant_builder, languages::common::Language,
+        operators::OperatorConfig, token::Token,
+    };
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn output(stdout: &str) -> process::Output {
+        process::Output {
+            status: process::ExitStatus::default(),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: vec![],
+        }
+    }
+
+    #[test]
+    fn test_test_durations() {
+        let config = config(Language::Noir);
+        let output = output(
+            "[app] Running 3 test functions\n\
+             [app] Testing test_fast... ok (12ms)\n\
+             [app] Testing test_slow... ok (1.5s)\n\
+             [app] Testing test_quiet... ok\n",
+        );
+
+        assert_eq!(
+            test_durations(&output, &*config, Duration::from_secs(3)),
+            vec![
+                ("test_fast".to_string(), Duration::from_millis(12)),
+                ("test_slow".to_string(), Duration::from_millis(1500)),
+                ("test_quiet".to_string(), Duration::from_secs(3)),
+            ]
+        );
+        assert_eq!(
+            test_outcomes(&output, &*config),
+            vec![
+                ("test_fast".to_string(), true),
+                ("test_slow".to_string(), true),
+                ("test_quiet".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mutant_duration() {
+        let config = config(Language::Noir);
+        let projects = tempdir().unwrap();
+        let mut baseline = Baseline::default();
+        let mut mutants = vec![];
+        for (name, seconds) in [("fast", 1), ("slow", 10)] {
+            let root = projects.path().join(name);
+            fs::create_dir_all(root.join("src")).unwrap();
+            fs::write(root.join("Nargo.toml"), "[package]\n").unwrap();
+            fs::write(root.join("src/main.nr"), "fn main() { 1 + 2; }\n").unwrap();
+            baseline.projects.insert(
+                root.canonicalize().unwrap(),
+                ProjectBaseline {
+                    build_duration: Duration::from_secs(seconds),
+                    test_duration: Duration::from_secs(seconds),
+                    test_durations: vec![(format!("test_{}", name), Duration::from_secs(seconds))],
+                },
+            );
+            mutants.extend(mutant_builder(
+                0,
+                Token::Plus,
+                (14, 15),
+                root.join("src/main.nr"),
+                false,
+                None,
+                &OperatorConfig::default(),
+            ));
+        }
+
+        assert_eq!(
+            baseline.mutant_duration(&mutants[0], &*config),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            baseline.mutant_duration(&mutants[1], &*config),
+            Duration::from_secs(20)
+        );
+        assert_eq!(baseline.build_duration(), Duration::from_secs(11));
+        assert_eq!(baseline.test_durations()[0].0, "test_slow");
+    }
+}
@@ -1,45 +1,96 @@
 use crate::cli::Args;
 use crate::config::LanguageConfig;
-use crate::handlers::scanner::ScanResult;
+use crate::formats::{write_report, OutputFormat, Report};
+use crate::handlers::{baseline::Baseline, scanner::ScanResult};
+use crate::operators::OperatorConfig;
 use crate::processor::process_mutants;
-use crate::reporter::{print_table, surviving_mutants_table};
-use crate::token::{random_token, token_as_bytes, token_transformer, MetaToken, Token};
+use crate::reporter::{
+    is_under_constrained, is_unverified_hint, mutation_test_summary_table, print_table, progress,
+    surviving_mutants_table, unbuildable_mutants_table, under_constrained_table,
+    unverified_hints_table,
+};
+use crate::token::{random_replacement, token_as_bytes, MetaToken, Token};
 use colored::*;
+use serde::Serialize;
 use std::{
     fmt,
     io::Result,
     path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
 };
 
+const TIMEOUT_ALLOWANCE: Duration = Duration::from_secs(5);
+
+/// A single replacement in a mutant's source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    original: Token,
+    mutation: Token,
+    span: (u32, u32),
+}
+
+impl Edit {
+    pub fn new(original: Token, mutation: Token, span: (u32, u32)) -> Self {
+        Self {
+            original,
+            mutation,
+            span,
+        }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.original
+    }
+
+    pub fn mutation(&self) -> &Token {
+        &self.mutation
+    }
+
+    pub fn span(&self) -> (u32, u32) {
+        self.span
+    }
+
+    fn overlaps(&self, other: &Edit) -> bool {
+        self.span.0 < other.span.1 && other.span.0 < self.span.1
+    }
+}
+
+/// One or more edits to a source file, applied and tested together.
+/// A first-order mutant has a single edit, a higher-order mutant one per combined mutant.
 #[derive(Debug, Clone)]
 pub struct Mutant {
     id: u32,
-    original: Token,
-    mutation: Token,
+    // Sorted by span, never empty
+    edits: Vec<Edit>,
+    // The replacement bytes of the first edit
     bytes: Vec<u8>,
-    span: (u32, u32),
     src_path: Box<PathBuf>,
     status: MutationStatus,
+    diagnostic: Option<String>,
+    duration: Option<Duration>,
+    killed_by: Option<String>,
+    function: Option<String>,
+    item_path: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MutationStatus {
     Pending,
     Survived,
     Killed,
     Unbuildable,
+    Timeout,
 }
 
 impl fmt::Display for Mutant {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Id: {:?}, Token: {:?}, Mutation: {:?} Bytes: {:?}, Span: {:?}, Source Path: {:?}, Status: {:?}",
+            "Id: {:?}, Edits: {:?}, Source Path: {:?}, Status: {:?}",
             self.id,
-            self.original,
-            self.mutation,
-            self.bytes,
-            self.span,
+            self.edits,
             self.src_path.display(),
             self.status,
         )
@@ -51,20 +102,33 @@ impl Mutant {
         self.id
     }
 
+    /// The edits the mutant makes, in source order
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    /// How many edits the mutant combines, `1` for a first-order mutant
+    pub fn order(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// The original token of the first edit
     pub fn token(&self) -> Token {
-        self.original.clone()
+        self.edits[0].original.clone()
     }
 
+    /// The replacement of the first edit
     pub fn mutation(&self) -> Token {
-        self.mutation.clone()
+        self.edits[0].mutation.clone()
     }
 
     pub fn bytes(&self) -> Vec<u8> {
         self.bytes.clone()
     }
 
+    /// The span of the first edit
     pub fn span(&self) -> (u32, u32) {
-        self.span
+        self.edits[0].span
     }
 
     pub fn path(&self) -> &Path {
@@ -72,11 +136,11 @@ impl Mutant {
     }
 
     pub fn span_start(&self) -> u32 {
-        self.span.0
+        self.span().0
     }
 
     pub fn span_end(&self) -> u32 {
-        self.span.1
+        self.span().1
     }
 
     pub fn status(&self) -> MutationStatus {
@@ -87,346 +151,422 @@ impl Mutant {
     pub fn set_status(&mut self, new_status: MutationStatus) {
         self.status = new_status;
     }
+
+    /// The leading lines of the compiler error, if the mutant was unbuildable
+    pub fn diagnostic(&self) -> Option<&str> {
+        self.diagnostic.as_deref()
+    }
+
+    pub fn set_diagnostic(&mut self, diagnostic: String) {
+        self.diagnostic = Some(diagnostic);
+    }
+
+    /// How long building and testing the mutant took
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = Some(duration);
+    }
+
+    /// The first test that failed against the mutant
+    pub fn killed_by(&self) -> Option<&str> {
+        self.killed_by.as_deref()
+    }
+
+    pub fn set_killed_by(&mut self, test_name: String) {
+        self.killed_by = Some(test_name);
+    }
+
+    /// The name of the function the mutant is in, if any
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+
+    /// The path of the innermost item the mutant is in, i.e: `my_crate::math::add`
+    pub fn item_path(&self) -> Option<&str> {
+        self.item_path.as_deref()
+    }
+
+    pub fn set_item(&mut self, function: Option<String>, item_path: Option<String>) {
+        self.function = function;
+        self.item_path = item_path;
+    }
+
+    /// A higher-order mutant making the edits of both mutants, or `None` if they are in
+    /// different files or any of their edits overlap
+    pub fn combine(&self, other: &Mutant, id: u32) -> Option<Mutant> {
+        if self.src_path != other.src_path
+            || self
+                .edits
+                .iter()
+                .any(|edit| other.edits.iter().any(|o| edit.overlaps(o)))
+        {
+            return None;
+        }
+
+        let mut edits: Vec<Edit> = self.edits.iter().chain(&other.edits).cloned().collect();
+        edits.sort_by_key(|edit| edit.span);
+        let bytes = token_as_bytes(&edits[0].mutation)
+            .unwrap_or_default()
+            .to_vec();
+        Some(Mutant {
+            id,
+            edits,
+            bytes,
+            src_path: self.src_path.clone(),
+            status: MutationStatus::Pending,
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: self.function.clone(),
+            item_path: self.item_path.clone(),
+        })
+    }
+}
+
+/// Which first-order mutants are combined into second-order mutants
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HigherOrder {
+    /// Mutants in the same function, combined before any are run
+    SameFunction,
+    /// Mutants in the same function that were each killed on their own, combined after the
+    /// first-order mutants are run
+    Killed,
+}
+
+impl HigherOrder {
+    pub fn list() -> String {
+        ["same-function", "killed"].join(", ")
+    }
 }
 
-pub fn mutants(meta_tokens: &Vec<MetaToken>, random: bool) -> Vec<Mutant> {
+impl FromStr for HigherOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "same-function" => Ok(HigherOrder::SameFunction),
+            "killed" => Ok(HigherOrder::Killed),
+            _ => Err(format!(
+                "No matching higher-order strategy. Current supported strategies are: {}",
+                HigherOrder::list()
+            )),
+        }
+    }
+}
+
+/// Second-order mutants, with ids counting up from `id`. Each mutant is combined with the next
+/// one in the same function whose edits don't overlap its own, so there are at most as many
+/// second-order mutants as first-order ones.
+pub fn higher_order_mutants(mutants: &[&Mutant], id: u32) -> Vec<Mutant> {
+    let mut combined: Vec<Mutant> = vec![];
+    for (i, mutant) in mutants.iter().enumerate() {
+        if mutant.item_path().is_none() {
+            continue;
+        }
+        let pair = mutants[i + 1..]
+            .iter()
+            .filter(|other| other.item_path() == mutant.item_path())
+            .find_map(|other| mutant.combine(other, id + combined.len() as u32));
+        combined.extend(pair);
+    }
+    combined
+}
+
+pub fn mutants(
+    meta_tokens: &Vec<MetaToken>,
+    random: bool,
+    operators: &OperatorConfig,
+) -> Vec<Mutant> {
     let mut mutants: Vec<Mutant> = vec![];
     for entry in meta_tokens {
         let path = entry.src().clone();
-        let maybe_mutant = mutant_builder(
-            entry.id(),
+        let built = mutant_builder(
+            mutants.len() as u32,
             entry.token().clone(),
             entry.span(),
             path,
             random,
+            entry.predicate(),
+            operators,
         );
-        match maybe_mutant {
-            None => continue,
-            Some(m) => mutants.push(m),
+        for mut m in built {
+            m.set_item(
+                entry.function().map(str::to_string),
+                entry.item_path().map(str::to_string),
+            );
+            mutants.push(m)
         }
     }
     mutants
 }
 
+/// Builds one mutant per replacement of the token, with ids counting up from `id`.
+/// Whole-predicate replacements are only built when the predicate's span is known.
 pub fn mutant_builder(
     id: u32,
     original: Token,
     span: (u32, u32),
     src_path: PathBuf,
     random: bool,
-) -> Option<Mutant> {
-    let mutation = match random {
-        true => random_token(),
-        false => token_transformer(original.clone()).unwrap(),
+    predicate: Option<(u32, u32)>,
+    operators: &OperatorConfig,
+) -> Vec<Mutant> {
+    if original == Token::Void {
+        return vec![];
+    }
+
+    let replacements = operators.replacements(&original);
+    // A random pick still only comes from the token's own replacements
+    let mutations = match random {
+        true => random_replacement(&replacements).into_iter().collect(),
+        false => replacements,
     };
-    match original {
-        Token::Equal => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::NotEqual => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Greater => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::GreaterEqual => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Less => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::LessEqual => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Ampersand => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Pipe => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Caret => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::ShiftLeft => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::ShiftRight => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Plus => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Minus => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Star => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Slash => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Percent => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Increment => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Decrement => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::PlusEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::MinusEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::StarEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::SlashEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::PercentEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::AmpersandEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::PipeEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::CaretEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::ShiftLeftEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::ShiftRightEquals => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::DoublePipe => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::DoubleAmpersand => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
-            bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
-            src_path: Box::new(src_path),
-            status: MutationStatus::Pending,
-        }),
-        Token::Bang => Some(Mutant {
-            id,
-            original,
-            mutation: mutation.clone(),
+
+    mutations
+        .into_iter()
+        .filter_map(|mutation| {
+            let span = match mutation {
+                Token::True | Token::False if original.is_relational() => predicate?,
+                _ => span,
+            };
+            Some((mutation, span))
+        })
+        .enumerate()
+        .map(|(n, (mutation, span))| Mutant {
+            id: id + n as u32,
             bytes: token_as_bytes(&mutation).unwrap().to_vec(),
-            span,
+            edits: vec![Edit::new(original.clone(), mutation, span)],
             src_path: Box::new(src_path.clone()),
             status: MutationStatus::Pending,
-        }),
-        Token::Void => None,
-    }
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: None,
+            item_path: None,
+        })
+        .collect()
 }
 
-pub fn mutate(args: Args, config: Box<dyn LanguageConfig>, results: &mut ScanResult) -> Result<()> {
+pub fn mutate(
+    args: Args,
+    config: Box<dyn LanguageConfig>,
+    results: &mut ScanResult,
+    baseline: &Baseline,
+) -> Result<()> {
     let mutants = results.mutants();
-    println!("{}", "Running tests...".green());
+    let timeout = mutant_timeout(&args, baseline);
+    let build_timeout = build_timeout(&args, baseline);
+    progress(
+        format!(
+            "Running tests (timeout per mutant: build {:.0?}, tests {:.0?})...",
+            build_timeout, timeout
+        )
+        .green(),
+    );
+
+    process_mutants(
+        mutants,
+        config.clone_box(),
+        Some(build_timeout),
+        Some(timeout),
+    );
+
+    if args.higher_order == Some(HigherOrder::Killed) {
+        let killed: Vec<&Mutant> = mutants
+            .iter()
+            .filter(|mutant| mutant.status() == MutationStatus::Killed)
+            .collect();
+        let mut combined = higher_order_mutants(&killed, mutants.len() as u32);
+        if !combined.is_empty() {
+            progress(
+                format!(
+                    "Running tests against {} second-order mutants...",
+                    combined.len()
+                )
+                .green(),
+            );
+            process_mutants(
+                &mut combined,
+                config.clone_box(),
+                Some(build_timeout),
+                Some(timeout),
+            );
+            mutants.extend(combined);
+        }
+    }
+
+    if args.format != OutputFormat::Text {
+        let report = Report::new("mutate", mutants, &*config, &args);
+        return write_report(&args.format, &report, args.output_path.as_deref());
+    }
 
-    process_mutants(mutants, args.clone(), config.clone_box());
+    // One summary over the first-order and any higher-order mutants
+    let summary = MutationSummary::from_mutants(mutants);
+    let score = calculate_mutation_score(
+        summary.killed as f64,
+        summary.unbuildable as f64,
+        summary.timed_out as f64,
+        summary.total as f64,
+        !args.exclude_unbuildable,
+        !args.exclude_timeouts,
+    );
+    print_table(
+        args.output_path.clone(),
+        mutation_test_summary_table(
+            summary.total as f64,
+            summary.pending as f64,
+            summary.unbuildable as f64,
+            summary.timed_out as f64,
+            summary.killed as f64,
+            summary.survived as f64,
+            score,
+        ),
+    )?;
+
+    if mutants.iter().any(|mutant| {
+        mutant.status() == MutationStatus::Survived
+            && !is_under_constrained(mutant)
+            && !is_unverified_hint(mutant)
+    }) {
+        print_table(args.output_path.clone(), surviving_mutants_table(mutants))?;
+    }
+
+    if mutants.iter().any(is_under_constrained) {
+        print_table(args.output_path.clone(), under_constrained_table(mutants))?;
+    }
+
+    if mutants.iter().any(is_unverified_hint) {
+        print_table(args.output_path.clone(), unverified_hints_table(mutants))?;
+    }
 
     if mutants
         .iter()
-        .any(|mutant| mutant.status() == MutationStatus::Survived)
+        .any(|mutant| mutant.status() == MutationStatus::Unbuildable)
     {
-        print_table(args.output_path, surviving_mutants_table(mutants))?;
+        print_table(args.output_path, unbuildable_mutants_table(mutants))?;
     }
 
     Ok(())
 }
 
-pub fn calculate_mutation_score(destroyed: f64, unbuildable: f64, total_mutants: f64) -> String {
-    let mutation_score = ((destroyed + unbuildable) / total_mutants) * 100.0;
+/// An explicit `--timeout` wins, otherwise the baseline test duration is scaled by
+/// `--timeout-multiplier`, plus a fixed allowance for machine load.
+pub fn mutant_timeout(args: &Args, baseline: &Baseline) -> Duration {
+    match args.timeout {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => baseline.test_duration().mul_f64(args.timeout_multiplier) + TIMEOUT_ALLOWANCE,
+    }
+}
+
+/// The same rule as `mutant_timeout`, scaling the baseline build duration instead. Noir unrolls
+/// loops at compile time, so a mutated loop bound can keep the compiler busy forever.
+pub fn build_timeout(args: &Args, baseline: &Baseline) -> Duration {
+    match args.timeout {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => baseline.build_duration().mul_f64(args.timeout_multiplier) + TIMEOUT_ALLOWANCE,
+    }
+}
+
+/// Unbuildable and timed out mutants are treated as detected, unless their
+/// `count_*` flag is false, in which case they are left out of the score entirely.
+/// With no mutants left to score, none survived, so the score is 100%.
+pub fn mutation_score(
+    destroyed: f64,
+    unbuildable: f64,
+    timed_out: f64,
+    total_mutants: f64,
+    count_unbuildable: bool,
+    count_timeouts: bool,
+) -> f64 {
+    let mut detected = destroyed;
+    let mut scored = total_mutants;
+
+    if count_unbuildable {
+        detected += unbuildable;
+    } else {
+        scored -= unbuildable;
+    }
+
+    if count_timeouts {
+        detected += timed_out;
+    } else {
+        scored -= timed_out;
+    }
+
+    if scored <= 0.0 {
+        return 100.0;
+    }
+    (detected / scored) * 100.0
+}
+
+pub fn calculate_mutation_score(
+    destroyed: f64,
+    unbuildable: f64,
+    timed_out: f64,
+    total_mutants: f64,
+    count_unbuildable: bool,
+    count_timeouts: bool,
+) -> String {
+    let mutation_score = mutation_score(
+        destroyed,
+        unbuildable,
+        timed_out,
+        total_mutants,
+        count_unbuildable,
+        count_timeouts,
+    );
     format!("{:.2}%", mutation_score)
 }
 
+/// The number of mutants in each state
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MutationSummary {
+    pub total: usize,
+    pub pending: usize,
+    pub unbuildable: usize,
+    pub timed_out: usize,
+    pub killed: usize,
+    pub survived: usize,
+}
+
+impl MutationSummary {
+    pub fn from_mutants(mutants: &[Mutant]) -> MutationSummary {
+        let mut summary = MutationSummary {
+            total: mutants.len(),
+            ..Default::default()
+        };
+        for mutant in mutants {
+            match mutant.status() {
+                MutationStatus::Pending => summary.pending += 1,
+                MutationStatus::Unbuildable => summary.unbuildable += 1,
+                MutationStatus::Timeout => summary.timed_out += 1,
+                MutationStatus::Killed => summary.killed += 1,
+                MutationStatus::Survived => summary.survived += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn score(&self, count_unbuildable: bool, count_timeouts: bool) -> f64 {
+        mutation_score(
+            self.killed as f64,
+            self.unbuildable as f64,
+            self.timed_out as f64,
+            self.total as f64,
+            count_unbuildable,
+            count_timeouts,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::operators::OperatorFamily;
+    use crate::token::{token_transformer, MutationSet};
 
     #[test]
     fn test_mutant_methods() {
@@ -436,12 +576,15 @@ mod tests {
         let span = (0, 1);
         let mutant = Mutant {
             id: 0,
-            original: token.clone(),
-            mutation: mutation.clone(),
+            edits: vec![Edit::new(token.clone(), mutation.clone(), span)],
             bytes: token_as_bytes(&token.clone()).unwrap().to_vec(),
-            span,
             src_path: Box::new(path.clone()),
             status: MutationStatus::Pending,
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: None,
+            item_path: None,
         };
 
         // Test token method
@@ -455,6 +598,13 @@ mod tests {
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
         assert_eq!(bytes_str, "==");
 
+        // Test edits method, a single edit for a first-order mutant
+        assert_eq!(mutant.order(), 1);
+        assert_eq!(
+            mutant.edits(),
+            &[Edit::new(token.clone(), mutation.clone(), span)]
+        );
+
         // Test span method
         assert_eq!(mutant.span(), span);
 
@@ -479,12 +629,15 @@ mod tests {
         let span = (10, 20);
         let mutant = Mutant {
             id: 42,
-            original: token.clone(),
-            mutation: mutation.clone(),
+            edits: vec![Edit::new(token.clone(), mutation.clone(), span)],
             bytes: token_as_bytes(&token.clone()).unwrap().to_vec(),
-            span,
             src_path: Box::new(path.clone()),
             status: MutationStatus::Pending,
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: None,
+            item_path: None,
         };
 
         // Test token method
@@ -498,6 +651,13 @@ mod tests {
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
         assert_eq!(bytes_str, "+");
 
+        // Test edits method, a single edit for a first-order mutant
+        assert_eq!(mutant.order(), 1);
+        assert_eq!(
+            mutant.edits(),
+            &[Edit::new(token.clone(), mutation.clone(), span)]
+        );
+
         // Test span method
         assert_eq!(mutant.span(), span);
 
@@ -524,12 +684,15 @@ mod tests {
         let span = (1000, 2000);
         let mutant = Mutant {
             id: 42,
-            original: token.clone(),
-            mutation: mutation.clone(),
+            edits: vec![Edit::new(token.clone(), mutation.clone(), span)],
             bytes: token_as_bytes(&token.clone()).unwrap().to_vec(),
-            span,
             src_path: Box::new(path.clone()),
             status: MutationStatus::Pending,
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: None,
+            item_path: None,
         };
 
         // Test token method
@@ -543,6 +706,13 @@ mod tests {
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
         assert_eq!(bytes_str, "*");
 
+        // Test edits method, a single edit for a first-order mutant
+        assert_eq!(mutant.order(), 1);
+        assert_eq!(
+            mutant.edits(),
+            &[Edit::new(token.clone(), mutation.clone(), span)]
+        );
+
         // Test span method
         assert_eq!(mutant.span(), span);
 
@@ -558,4 +728,142 @@ mod tests {
         // Test status method
         assert_eq!(mutant.status(), MutationStatus::Pending);
     }
+
+    fn first_order(id: u32, span: (u32, u32), item_path: Option<&str>) -> Mutant {
+        let mut mutant = Mutant {
+            id,
+            edits: vec![Edit::new(Token::Plus, Token::Minus, span)],
+            bytes: b"-".to_vec(),
+            src_path: Box::new(PathBuf::from("src/main.nr")),
+            status: MutationStatus::Killed,
+            diagnostic: None,
+            duration: None,
+            killed_by: None,
+            function: None,
+            item_path: None,
+        };
+        mutant.set_item(
+            item_path.map(|path| path.rsplit("::").next().unwrap().to_string()),
+            item_path.map(str::to_string),
+        );
+        mutant
+    }
+
+    #[test]
+    fn test_combine() {
+        let a = first_order(0, (20, 21), Some("main::add"));
+        let b = first_order(1, (10, 11), Some("main::add"));
+        let combined = a.combine(&b, 2).unwrap();
+        assert_eq!(combined.id(), 2);
+        assert_eq!(combined.order(), 2);
+        assert_eq!(combined.status(), MutationStatus::Pending);
+        assert_eq!(combined.function(), Some("add"));
+        // Edits are kept in source order
+        assert_eq!(combined.span(), (10, 11));
+        assert_eq!(combined.edits()[1].span(), (20, 21));
+
+        // Overlapping edits can't be combined
+        assert!(a.combine(&first_order(1, (20, 23), None), 2).is_none());
+        assert!(a.combine(&a, 2).is_none());
+        // Adjacent ones can
+        assert!(a.combine(&first_order(1, (21, 22), None), 2).is_some());
+
+        let mut other_file = first_order(1, (10, 11), Some("main::add"));
+        *other_file.src_path = PathBuf::from("src/lib.nr");
+        assert!(a.combine(&other_file, 2).is_none());
+    }
+
+    #[test]
+    fn test_higher_order_mutants() {
+        let mutants = [
+            first_order(0, (10, 11), Some("main::add")),
+            first_order(1, (10, 13), Some("main::add")),
+            first_order(2, (20, 21), Some("main::sub")),
+            first_order(3, (30, 31), Some("main::add")),
+            first_order(4, (40, 41), None),
+            first_order(5, (50, 51), None),
+        ];
+        let refs: Vec<&Mutant> = mutants.iter().collect();
+        let combined = higher_order_mutants(&refs, 6);
+
+        let spans: Vec<Vec<(u32, u32)>> = combined
+            .iter()
+            .map(|mutant| mutant.edits().iter().map(Edit::span).collect())
+            .collect();
+        // Overlapping mutants are skipped, mutants outside functions are never paired
+        assert_eq!(
+            spans,
+            vec![vec![(10, 11), (30, 31)], vec![(10, 13), (30, 31)]]
+        );
+        assert_eq!(
+            combined.iter().map(Mutant::id).collect::<Vec<_>>(),
+            vec![6, 7]
+        );
+    }
+
+    #[test]
+    fn test_higher_order_parsing() {
+        assert_eq!(
+            "same-function".parse::<HigherOrder>(),
+            Ok(HigherOrder::SameFunction)
+        );
+        assert_eq!("Killed".parse::<HigherOrder>(), Ok(HigherOrder::Killed));
+        assert!("third".parse::<HigherOrder>().is_err());
+    }
+
+    #[test]
+    fn test_random_mutants_use_replacements() {
+        let operators = OperatorConfig::new(MutationSet::Exhaustive, OperatorFamily::all(), vec![]);
+        for token in [
+            Token::Less,
+            Token::Statement("c = 5;".to_string()),
+            Token::Literal("5".to_string()),
+        ] {
+            let replacements = operators.replacements(&token);
+            for _ in 0..10 {
+                let mutants = mutant_builder(
+                    0,
+                    token.clone(),
+                    (0, 1),
+                    PathBuf::from("src/main.nr"),
+                    true,
+                    Some((0, 5)),
+                    &operators,
+                );
+                assert_eq!(mutants.len(), 1);
+                assert!(replacements.contains(&mutants[0].mutation()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_mutation_score_timeouts() {
+        assert_eq!(
+            calculate_mutation_score(5.0, 1.0, 2.0, 10.0, true, true),
+            "80.00%"
+        );
+        assert_eq!(
+            calculate_mutation_score(5.0, 1.0, 2.0, 10.0, true, false),
+            "75.00%"
+        );
+    }
+
+    #[test]
+    fn test_calculate_mutation_score_unbuildable() {
+        assert_eq!(
+            calculate_mutation_score(5.0, 2.0, 0.0, 10.0, true, true),
+            "70.00%"
+        );
+        assert_eq!(
+            calculate_mutation_score(5.0, 2.0, 0.0, 10.0, false, true),
+            "62.50%"
+        );
+    }
+
+    #[test]
+    fn test_mutation_score_nothing_scored() {
+        assert_eq!(mutation_score(0.0, 0.0, 0.0, 0.0, true, true), 100.0);
+        assert_eq!(mutation_score(0.0, 2.0, 3.0, 5.0, false, false), 100.0);
+        assert_eq!(MutationSummary::default().score(true, true), 100.0);
+    }
 }
@@ -2,14 +2,15 @@ use crate::{
     cli::Args,
     config::LanguageConfig,
     file_manager::scan_for_excluded_dirs,
-    handlers::mutator::{mutants, Mutant},
+    handlers::mutator::{higher_order_mutants, mutants, HigherOrder, Mutant},
+    operators::OperatorConfig,
     reporter::count_tests,
     token::MetaToken,
-    utils::collect_tokens,
+    utils::{collect_tokens, declarations},
 };
 use colored::*;
 use std::{
-    io::{Error, ErrorKind, Result},
+    io::{Error, Result},
     path::PathBuf,
 };
 
@@ -61,6 +62,7 @@ impl ScanResult {
 }
 
 pub fn scan(args: Args, config: Box<dyn LanguageConfig>) -> Result<ScanResult> {
+    let operators = OperatorConfig::from_args(&args)?;
     let source_path = args.source_path;
 
     let paths = if source_path.is_file() {
@@ -71,7 +73,7 @@ pub fn scan(args: Args, config: Box<dyn LanguageConfig>) -> Result<ScanResult> {
                 "No {} files found... Are you in the right directory?",
                 config.name().red()
             );
-            Error::new(ErrorKind::Other, err_msg)
+            Error::other(err_msg)
         })?
     };
 
@@ -96,9 +98,28 @@ pub fn scan(args: Args, config: Box<dyn LanguageConfig>) -> Result<ScanResult> {
         paths.clone()
     };
 
-    let meta_tokens = collect_tokens(paths_to_scan, config).expect("No tokens found");
+    // Call sites and field accesses may use declarations from files without tests
+    let declarations = declarations(&paths, &*config);
+    let mut meta_tokens =
+        collect_tokens(paths_to_scan, &declarations, config).expect("No tokens found");
+    meta_tokens.retain(|token| operators.enables(token.token()));
+
+    if let Some(function) = &args.function {
+        meta_tokens.retain(|token| in_function(token.item_path(), function));
+        if meta_tokens.is_empty() {
+            return Err(Error::other(format!(
+                "No mutable tokens found in function {}",
+                function.red()
+            )));
+        }
+    }
 
-    let mutants = mutants(&meta_tokens, args.random);
+    let mut mutants = mutants(&meta_tokens, args.random, &operators);
+    if args.higher_order == Some(HigherOrder::SameFunction) {
+        let first_order: Vec<&Mutant> = mutants.iter().collect();
+        let combined = higher_order_mutants(&first_order, mutants.len() as u32);
+        mutants.extend(combined);
+    }
 
     Ok(ScanResult::new(
         paths,
@@ -108,3 +129,25 @@ pub fn scan(args: Args, config: Box<dyn LanguageConfig>) -> Result<ScanResult> {
         mutants,
     ))
 }
+
+/// Whether an item path names the function given by `function`.
+/// i.e: `my_crate::math::add` matches `add`, `math::add` and `my_crate::math::add`
+pub fn in_function(item_path: Option<&str>, function: &str) -> bool {
+    item_path.is_some_and(|path| path == function || path.ends_with(&format!("::{}", function)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_function() {
+        let path = Some("my_crate::math::add");
+        assert!(in_function(path, "add"));
+        assert!(in_function(path, "math::add"));
+        assert!(in_function(path, "my_crate::math::add"));
+        assert!(!in_function(path, "dd"));
+        assert!(!in_function(path, "sub"));
+        assert!(!in_function(None, "add"));
+    }
+}
@@ -1,16 +1,24 @@
 use std::{
-    fs::{self, File, OpenOptions},
-    io::{self, Write},
-    path::PathBuf,
+    fs, io,
+    path::{Path, PathBuf},
     process::{self, Command},
-    sync::Mutex,
+    time::Duration,
 };
 
 use fs_extra::error::Error;
 use regex::Regex;
 use tempfile::{Builder, TempDir};
-
-use crate::{config::LanguageConfig, handlers::mutator::Mutant, languages::common::Language};
+use toml::{Table, Value};
+use walkdir::WalkDir;
+
+use crate::{
+    config::LanguageConfig,
+    handlers::mutator::Mutant,
+    languages::common::Language,
+    lexer::{self, Lexeme},
+    syntax::{self, SyntaxTree},
+    utils::{isolate_process_group, wait_with_timeout},
+};
 
 const NAME: &str = "Noir";
 const EXT: &str = "nr";
@@ -19,6 +27,8 @@ const TEST_COMMAND: &str = "test";
 const BUILD_COMMAND: &str = "build";
 const MANIFEST_NAME: &str = "Nargo.toml";
 const FILTER_TESTS: bool = true;
+/// The first nargo release whose `for` loops accept `..=`
+const INCLUSIVE_RANGE_VERSION: (u32, u32, u32) = (0, 35, 0);
 
 #[derive(Clone)]
 pub struct NoirConfig;
@@ -40,6 +50,23 @@ impl LanguageConfig for NoirConfig {
         TEST_RUNNER
     }
 
+    fn test_runner_version(&self) -> Option<String> {
+        // i.e: `nargo version = 0.36.0`
+        let output = Command::new(self.test_runner())
+            .arg("--version")
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let first_line = stdout.lines().next()?;
+        Some(first_line.rsplit(['=', ' ']).next()?.trim().to_string())
+    }
+
+    fn supports_inclusive_ranges(&self) -> bool {
+        self.test_runner_version()
+            .and_then(|version| parse_version(&version))
+            .is_some_and(|version| version >= INCLUSIVE_RANGE_VERSION)
+    }
+
     fn test_command(&self) -> &'static str {
         TEST_COMMAND
     }
@@ -64,95 +91,297 @@ impl LanguageConfig for NoirConfig {
         Some(Regex::new(r"#\[test(\(.*\))?\]\s+fn\s+\w+\(\)\s*\{[^}]*\}").unwrap())
     }
 
-    fn comment_regex(&self) -> Regex {
-        Regex::new(r"//.*|/\*(?s:.*?)\*/").unwrap()
+    fn lex(&self, source: &str) -> Vec<Lexeme> {
+        lexer::lex(source)
+    }
+
+    fn syntax_tree(&self, source: &str, lexemes: &[Lexeme]) -> SyntaxTree {
+        syntax::parse(source, lexemes)
+    }
+
+    fn module_path(&self, project_root: &Path, src_path: &Path) -> Vec<String> {
+        let crate_name = fs::read_to_string(project_root.join(MANIFEST_NAME))
+            .ok()
+            .and_then(|manifest| manifest.parse::<Table>().ok())
+            .and_then(|manifest| {
+                manifest
+                    .get("package")?
+                    .get("name")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| "crate".to_string());
+
+        let src_path = src_path
+            .canonicalize()
+            .unwrap_or_else(|_| src_path.to_path_buf());
+        let Ok(relative) = src_path.strip_prefix(project_root.join("src")) else {
+            return vec![crate_name];
+        };
+
+        // i.e: `src/math/add.nr` is `my_crate::math::add`, `src/math/mod.nr` is `my_crate::math`
+        let mut path = vec![crate_name];
+        path.extend(
+            relative
+                .with_extension("")
+                .iter()
+                .map(|component| component.to_string_lossy().into_owned()),
+        );
+        if (path.len() == 2 && matches!(path[1].as_str(), "main" | "lib"))
+            || path.last().is_some_and(|m| m == "mod")
+        {
+            path.pop();
+        }
+        path
     }
 
-    fn literal_regex(&self) -> Regex {
-        Regex::new(r#""([^"\\]|\\.)*""#).unwrap()
+    fn test_outcome_regex(&self) -> Regex {
+        // i.e: `[hello_world] Testing test_main... ok`, or `... ok (1.25s)` with its duration
+        Regex::new(r"Testing (\S+?)\s*\.\.\.\s*(ok|FAIL)(?:\s*\((\d+(?:\.\d+)?)(ms|s)\))?").unwrap()
     }
 
     fn setup_test_infrastructure(&self) -> Result<TempDir, Error> {
-        // Create a temp directory with a specific prefix
+        // Create a temp directory with a specific prefix.
+        // The user's project is copied into it later, see `copy_project()`
         let temp_dir = Builder::new()
             .prefix("Hunter_temp_mutations_")
             .tempdir_in(std::env::temp_dir())?;
 
-        // Inside /temp, create a src/ directory
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(src_dir.clone())?;
-
-        let mut manifest = File::create(temp_dir.path().join(self.manifest_name()))?;
+        Ok(temp_dir)
+    }
 
-        write!(
-            manifest,
-            r#"[package]
-name = "hunter_temp"
-type = "lib"
-authors = ["Hunter"]
-compiler_version = ">=0.22.0"
+    fn copy_project(&self, project_root: &Path, workspace: &Path) -> io::Result<()> {
+        let entries = WalkDir::new(project_root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                !(name.starts_with('.') || entry.file_type().is_dir() && name == "target")
+            });
+
+        for entry in entries {
+            let entry = entry?;
+            let relative_path = entry.path().strip_prefix(project_root).unwrap();
+            let destination = workspace.join(relative_path);
+
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&destination)?;
+            } else {
+                fs::copy(entry.path(), &destination)?;
+            }
+        }
 
-[dependencies]
-        "#
+        // Relative path dependencies would resolve against the workspace, so make them absolute
+        let manifest_path = workspace.join(self.manifest_name());
+        let manifest = fs::read_to_string(&manifest_path)?;
+        fs::write(
+            manifest_path,
+            absolute_path_dependencies(&manifest, project_root)?,
         )?;
-        let _ = File::create(src_dir.join("lib.nr"))?;
 
-        Ok(temp_dir)
+        Ok(())
     }
 
     fn copy_src_file(
         &self,
-        temp_dir: &TempDir,
+        workspace: &Path,
+        project_root: &Path,
         mutant: &Mutant,
-        mutex: Option<&Mutex<()>>,
     ) -> io::Result<PathBuf> {
-        // For Noir, mutex should always be Some
-        if mutex.is_none() {
-            return Err(io::Error::new(
+        // The mutant replaces the original file at its real module path
+        let src_path = mutant.path().canonicalize()?;
+        let relative_path = src_path.strip_prefix(project_root).map_err(|_| {
+            io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "Mutex is not initialized",
-            ));
-        }
-
-        let src_dir = temp_dir.path().join("src");
+                format!(
+                    "{} is not part of the project at {}",
+                    src_path.display(),
+                    project_root.display()
+                ),
+            )
+        })?;
 
-        let temp_file = src_dir.join(format!("mutation_{}.{}", mutant.id(), EXT));
-        fs::copy(mutant.path(), &temp_file)?;
+        let temp_file = workspace.join(relative_path);
+        fs::copy(&src_path, &temp_file)?;
 
-        // Lock the mutex before writing to the file
-        let _guard = mutex.unwrap().lock().unwrap();
+        Ok(temp_file)
+    }
 
-        let mut lib_file = OpenOptions::new()
-            .write(true)
-            .open(src_dir.join(format!("lib.{}", EXT)))?;
-        writeln!(lib_file, "mod mutation_{};", mutant.id())?;
+    fn test_mutant_project(
+        &self,
+        dir: &Path,
+        timeout: Option<Duration>,
+    ) -> Option<Box<process::Output>> {
+        let child = isolate_process_group(
+            Command::new(self.test_runner())
+                .arg(self.test_command())
+                .current_dir(dir)
+                .stderr(process::Stdio::piped())
+                .stdout(process::Stdio::piped()),
+        )
+        .spawn()
+        .expect("Failed to execute command");
+
+        wait_with_timeout(child, timeout)
+            .expect("Failed to wait on child")
+            .map(Box::new)
+    }
 
-        Ok(temp_file)
+    fn build_mutant_project(
+        &self,
+        dir: &Path,
+        timeout: Option<Duration>,
+    ) -> Option<Box<process::Output>> {
+        let child = isolate_process_group(
+            Command::new(self.test_runner())
+                .arg(self.build_command())
+                .current_dir(dir)
+                .stderr(process::Stdio::piped())
+                .stdout(process::Stdio::piped()),
+        )
+        .spawn()
+        .expect("Failed to execute build command");
+
+        wait_with_timeout(child, timeout)
+            .expect("Failed to wait on child")
+            .map(Box::new)
     }
 
-    fn test_mutant_project(&self) -> Box<process::Output> {
-        let child = Command::new(self.test_runner())
-            .arg(self.test_command())
-            .stderr(process::Stdio::piped())
-            .stdout(process::Stdio::piped())
-            .spawn()
-            .expect("Failed to execute command");
+    fn clone_box(&self) -> Box<dyn LanguageConfig + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
 
-        Box::new(child.wait_with_output().expect("Failed to wait on child"))
+/// Rewrites every `{ path = "..." }` dependency in a Nargo.toml to an absolute path.
+fn absolute_path_dependencies(manifest: &str, project_root: &Path) -> io::Result<String> {
+    let mut manifest: Table = manifest
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if let Some(Value::Table(dependencies)) = manifest.get_mut("dependencies") {
+        for (_, dependency) in dependencies.iter_mut() {
+            if let Some(Value::String(path)) = dependency.get_mut("path") {
+                let absolute = project_root.join(&path);
+                *path = absolute
+                    .canonicalize()
+                    .unwrap_or(absolute)
+                    .to_string_lossy()
+                    .into_owned();
+            }
+        }
     }
 
-    fn build_mutant_project(&self) -> Box<process::Output> {
-        let child = Command::new(self.test_runner())
-            .arg(self.build_command())
-            .stderr(process::Stdio::piped())
-            .stdout(process::Stdio::piped())
-            .spawn()
-            .expect("Failed to execute build command");
+    toml::to_string(&manifest).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Parses a version like `0.36.0` or `1.0.0-beta.3`, ignoring any pre-release suffix
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let release = version.split(['-', '+']).next()?;
+    let mut parts = release.split('.').map(|part| part.parse::<u32>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_absolute_path_dependencies() {
+        let projects = tempdir().unwrap();
+        let project_root = projects.path().join("app");
+        fs::create_dir_all(projects.path().join("lib")).unwrap();
+        fs::create_dir_all(&project_root).unwrap();
+
+        let manifest = r#"
+[package]
+name = "app"
+type = "bin"
 
-        Box::new(child.wait_with_output().expect("Failed to wait on child"))
+[dependencies]
+lib = { path = "../lib" }
+missing = { path = "deps/missing" }
+std_lib = { tag = "v0.1.0", git = "https://github.com/noir-lang/noir" }
+"#;
+        let rewritten: Table = absolute_path_dependencies(manifest, &project_root)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let dependencies = rewritten["dependencies"].as_table().unwrap();
+
+        assert_eq!(
+            dependencies["lib"]["path"].as_str().unwrap(),
+            projects
+                .path()
+                .join("lib")
+                .canonicalize()
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+        // A path that doesn't exist yet is still made absolute, for nargo to report
+        assert_eq!(
+            dependencies["missing"]["path"].as_str().unwrap(),
+            project_root.join("deps/missing").to_str().unwrap()
+        );
+        assert_eq!(dependencies["std_lib"]["tag"].as_str(), Some("v0.1.0"));
+        assert!(dependencies["std_lib"].get("path").is_none());
+        assert_eq!(rewritten["package"]["name"].as_str(), Some("app"));
     }
 
-    fn clone_box(&self) -> Box<dyn LanguageConfig + Send + Sync> {
-        Box::new(self.clone())
+    #[test]
+    fn test_absolute_path_dependencies_invalid_manifest() {
+        let error = absolute_path_dependencies("[package", Path::new(".")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_copy_project() {
+        let project = tempdir().unwrap();
+        let workspace = tempdir().unwrap();
+        let root = project.path();
+        fs::write(
+            root.join(MANIFEST_NAME),
+            "[package]\nname = \"app\"\ntype = \"bin\"\n\n[dependencies]\nlib = { path = \"lib\" }\n",
+        )
+        .unwrap();
+        for dir in ["src/nested", "lib", "target/debug", ".git"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "src/main.nr",
+            "src/nested/mod.nr",
+            "target/debug/app.json",
+            ".git/HEAD",
+            ".env",
+            "src/.hidden.nr",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        NoirConfig.copy_project(root, workspace.path()).unwrap();
+
+        let workspace = workspace.path();
+        assert!(workspace.join("src/main.nr").is_file());
+        assert!(workspace.join("src/nested/mod.nr").is_file());
+        assert!(workspace.join("lib").is_dir());
+        // Build output and dotfiles are skipped
+        assert!(!workspace.join("target").exists());
+        assert!(!workspace.join(".git").exists());
+        assert!(!workspace.join(".env").exists());
+        assert!(!workspace.join("src/.hidden.nr").exists());
+
+        let manifest: Table = fs::read_to_string(workspace.join(MANIFEST_NAME))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            manifest["dependencies"]["lib"]["path"].as_str().unwrap(),
+            root.join("lib").canonicalize().unwrap().to_str().unwrap()
+        );
     }
 }
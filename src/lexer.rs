@@ -0,0 +1,582 @@
+//! A single-pass lexer for Noir source files.
+//!
+//! Mutation candidates are found on the lexer's output rather than on the raw text, so that
+//! operators are found regardless of formatting and never inside comments, string literals,
+//! attributes, type annotations or generic argument lists.
+
+use std::ops::Range;
+
+/// Punctuation, longest first so that the lexer always takes the longest match
+const PUNCTUATION: [&str; 48] = [
+    "<<=", ">>=", "..=", "...", "==", "!=", "<=", ">=", "<<", ">>", "&&", "||", "++", "--", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "->", "=>", "::", "..", "<", ">", "=", "!", "+", "-",
+    "*", "/", "%", "&", "|", "^", "(", ")", "[", "]", "{", "}", ",", ";", ":", ".",
+];
+
+/// Keywords that can't end an operand, so an operator following them is a prefix operator
+const KEYWORDS: [&str; 24] = [
+    "as",
+    "assert",
+    "assert_eq",
+    "break",
+    "comptime",
+    "constrain",
+    "continue",
+    "else",
+    "fn",
+    "for",
+    "global",
+    "if",
+    "impl",
+    "in",
+    "let",
+    "mod",
+    "mut",
+    "pub",
+    "return",
+    "struct",
+    "trait",
+    "type",
+    "unconstrained",
+    "use",
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LexemeKind {
+    /// Identifiers and keywords
+    Ident,
+    /// Integer literals, i.e: `42`, `0xff`, `1_000`
+    Int,
+    /// String, raw string and format string literals
+    Str,
+    /// A whole attribute, i.e: `#[test(should_fail)]`
+    Attribute,
+    /// An operator or delimiter
+    Punct,
+    /// The `<` and `>` brackets of a generic parameter or argument list
+    GenericBracket,
+    /// Any character the lexer doesn't recognise
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lexeme {
+    kind: LexemeKind,
+    span: Range<usize>,
+    // Whether the lexeme is part of a type, i.e: `&mut [u8; 4]` in `x: &mut [u8; 4]`
+    in_type: bool,
+    // Whether an operator is used as a binary (infix) operator
+    binary: bool,
+}
+
+impl Lexeme {
+    fn new(kind: LexemeKind, span: Range<usize>) -> Lexeme {
+        Lexeme {
+            kind,
+            span,
+            in_type: false,
+            binary: false,
+        }
+    }
+
+    pub fn kind(&self) -> LexemeKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.clone()]
+    }
+
+    pub fn in_type(&self) -> bool {
+        self.in_type
+    }
+
+    pub fn binary(&self) -> bool {
+        self.binary
+    }
+
+    pub fn is_punct(&self, source: &str, punct: &str) -> bool {
+        self.kind == LexemeKind::Punct && self.text(source) == punct
+    }
+}
+
+/// Splits Noir source into lexemes. Comments and whitespace are dropped.
+pub fn lex(source: &str) -> Vec<Lexeme> {
+    let lexemes = scan(source);
+    classify(source, lexemes)
+}
+
+fn scan(source: &str) -> Vec<Lexeme> {
+    let bytes = source.as_bytes();
+    let mut lexemes = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if source[i..].starts_with("/*") {
+            i = block_comment_end(bytes, i);
+        } else if c == b'"' {
+            i = string_end(bytes, i + 1);
+            lexemes.push(Lexeme::new(LexemeKind::Str, start..i));
+        } else if c == b'f'
+            && bytes.get(i + 1) == Some(&b'"')
+            && (i == 0 || !is_ident_byte(bytes[i - 1]))
+        {
+            i = string_end(bytes, i + 2);
+            lexemes.push(Lexeme::new(LexemeKind::Str, start..i));
+        } else if let Some(end) = raw_string_end(bytes, i) {
+            i = end;
+            lexemes.push(Lexeme::new(LexemeKind::Str, start..i));
+        } else if c == b'#' && matches!(bytes.get(i + 1), Some(b'[') | Some(b'!')) {
+            i = attribute_end(bytes, i);
+            lexemes.push(Lexeme::new(LexemeKind::Attribute, start..i));
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && is_ident_byte(bytes[i]) {
+                i += 1;
+            }
+            lexemes.push(Lexeme::new(LexemeKind::Int, start..i));
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && is_ident_byte(bytes[i]) {
+                i += 1;
+            }
+            lexemes.push(Lexeme::new(LexemeKind::Ident, start..i));
+        } else if let Some(punct) = PUNCTUATION.iter().find(|p| source[i..].starts_with(**p)) {
+            i += punct.len();
+            lexemes.push(Lexeme::new(LexemeKind::Punct, start..i));
+        } else {
+            // Skip a whole (possibly multi-byte) character
+            i += source[i..].chars().next().map_or(1, char::len_utf8);
+            lexemes.push(Lexeme::new(LexemeKind::Unknown, start..i));
+        }
+    }
+
+    lexemes
+}
+
+/// Block comments nest in Noir
+fn block_comment_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// The end of a string literal whose contents start at `i`
+fn string_end(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// The end of a raw string literal starting at `start`, i.e: `r"..."` or `r#"..."#`
+fn raw_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    if bytes[start] != b'r' || (start > 0 && is_ident_byte(bytes[start - 1])) {
+        return None;
+    }
+    let hashes = bytes[start + 1..]
+        .iter()
+        .take_while(|&&b| b == b'#')
+        .count();
+    let open = start + 1 + hashes;
+    if bytes.get(open) != Some(&b'"') {
+        return None;
+    }
+
+    let mut closing = vec![b'"'];
+    closing.extend(std::iter::repeat(b'#').take(hashes));
+
+    let mut i = open + 1;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(&closing) {
+            return Some(i + closing.len());
+        }
+        i += 1;
+    }
+    Some(bytes.len())
+}
+
+/// The end of an attribute starting at `start`, i.e: `#[test]` or `#![allow(unused)]`
+fn attribute_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            b'"' => {
+                i = string_end(bytes, i + 1);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Where a type that started at `depth` ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TypeEnd {
+    /// After a `:`, i.e: `let x: T = ...`, `fn f(x: T, ...)` or `struct S { x: T, ... }`
+    Annotation,
+    /// After a `->`, i.e: `-> T {`
+    Return,
+    /// An item header, i.e: `impl<T> Foo<T> for Bar {` or `struct S<T> {`
+    Header,
+    /// A type alias, i.e: `type T = U;`
+    Alias,
+}
+
+struct TypeContext {
+    end: TypeEnd,
+    depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Delimiter {
+    Paren,
+    Bracket,
+    Block,
+    /// The body of a struct definition, where `name: T` declares a field rather than initialising it
+    StructBody,
+}
+
+/// Marks the lexemes that are generic brackets, part of a type or binary operators.
+fn classify(source: &str, mut lexemes: Vec<Lexeme>) -> Vec<Lexeme> {
+    let mut result: Vec<Lexeme> = Vec::with_capacity(lexemes.len());
+    let mut delimiters: Vec<Delimiter> = Vec::new();
+    let mut types: Vec<TypeContext> = Vec::new();
+    // Open generic lists, each with the number of open types when it was opened
+    let mut generics: Vec<usize> = Vec::new();
+    // Whether the next `{` opens a struct definition body
+    let mut struct_body = false;
+    // The delimiter depth of the closure parameter list being lexed, if any
+    let mut closure_params: Option<usize> = None;
+
+    lexemes.reverse();
+    while let Some(mut lexeme) = lexemes.pop() {
+        let text = lexeme.text(source);
+        let depth = delimiters.len();
+        let previous = result.last();
+        let previous_text = previous.map_or("", |l| l.text(source));
+        let before_previous = result
+            .len()
+            .checked_sub(2)
+            .map_or("", |i| result[i].text(source));
+
+        // Close the innermost types this lexeme ends
+        while let Some(context) = types.last() {
+            let at_base = depth == context.depth;
+            let ends = match lexeme.kind {
+                LexemeKind::Punct => match text {
+                    ")" | "]" | "}" => depth <= context.depth,
+                    "{" | ";" => at_base,
+                    "=" => at_base && context.end != TypeEnd::Alias,
+                    "," | "|" => at_base && context.end != TypeEnd::Header,
+                    _ => false,
+                },
+                LexemeKind::Ident => at_base && text == "where" && context.end == TypeEnd::Return,
+                _ => false,
+            };
+            let in_generics = generics.last().is_some_and(|&n| n >= types.len());
+            if ends && !in_generics {
+                types.pop();
+            } else {
+                break;
+            }
+        }
+
+        let in_type = !types.is_empty() || !generics.is_empty();
+        lexeme.in_type = in_type;
+
+        match lexeme.kind {
+            LexemeKind::Punct => {
+                let opens_generics = text == "<"
+                    && (in_type
+                        || previous_text == "::"
+                        || previous_text == "impl"
+                        || (previous.is_some_and(|l| l.kind == LexemeKind::Ident)
+                            && before_previous == "fn"));
+
+                if opens_generics {
+                    lexeme.kind = LexemeKind::GenericBracket;
+                    generics.push(types.len());
+                    result.push(lexeme);
+                    continue;
+                }
+
+                if !generics.is_empty() && text.starts_with('>') {
+                    // Split `>>`, `>=` and `>>=` so that each `>` closes one generic list
+                    let start = lexeme.span.start;
+                    generics.pop();
+                    let mut bracket = Lexeme::new(LexemeKind::GenericBracket, start..start + 1);
+                    bracket.in_type = true;
+                    result.push(bracket);
+                    if lexeme.span.len() > 1 {
+                        lexemes.push(Lexeme::new(LexemeKind::Punct, start + 1..lexeme.span.end));
+                    }
+                    continue;
+                }
+
+                match text {
+                    "(" => delimiters.push(Delimiter::Paren),
+                    "[" => delimiters.push(Delimiter::Bracket),
+                    "{" if struct_body => {
+                        delimiters.push(Delimiter::StructBody);
+                        struct_body = false;
+                    }
+                    "{" => delimiters.push(Delimiter::Block),
+                    ")" | "]" | "}" => {
+                        delimiters.pop();
+                    }
+                    ";" => struct_body = false,
+                    _ => {}
+                }
+
+                let operand_before = previous.is_some_and(|l| ends_operand(l, source));
+
+                if text == "|" && closure_params == Some(depth) {
+                    // The end of a closure's parameter list
+                    closure_params = None;
+                } else if (text == "|" || text == "||") && !operand_before && !in_type {
+                    // The start of a closure
+                    if text == "|" {
+                        closure_params = Some(depth);
+                    }
+                } else {
+                    lexeme.binary = operand_before && !in_type && is_operator(text);
+                }
+
+                if text == ":" && !in_type {
+                    // i.e: `S { a: x < y }`
+                    let field_init = delimiters.last() == Some(&Delimiter::Block)
+                        && previous.is_some_and(|l| l.kind == LexemeKind::Ident)
+                        && matches!(before_previous, "{" | ",")
+                        && closure_params.is_none();
+                    if !field_init {
+                        types.push(TypeContext {
+                            end: TypeEnd::Annotation,
+                            depth,
+                        });
+                    }
+                } else if text == "->" {
+                    types.push(TypeContext {
+                        end: TypeEnd::Return,
+                        depth,
+                    });
+                }
+            }
+            LexemeKind::Ident if !in_type => {
+                let end = match text {
+                    "impl" | "trait" | "where" => Some(TypeEnd::Header),
+                    "struct" => {
+                        struct_body = true;
+                        Some(TypeEnd::Header)
+                    }
+                    "type" => Some(TypeEnd::Alias),
+                    _ => None,
+                };
+                if let Some(end) = end {
+                    types.push(TypeContext { end, depth });
+                }
+            }
+            _ => {}
+        }
+
+        result.push(lexeme);
+    }
+
+    result
+}
+
+/// Whether the punctuation is an operator rather than a delimiter or separator
+fn is_operator(punct: &str) -> bool {
+    !matches!(
+        punct,
+        "(" | ")"
+            | "["
+            | "]"
+            | "{"
+            | "}"
+            | ","
+            | ";"
+            | ":"
+            | "::"
+            | "."
+            | ".."
+            | "..="
+            | "..."
+            | "->"
+            | "=>"
+            | "="
+    )
+}
+
+/// Whether `text` is a Noir keyword, i.e: `let`
+pub fn is_keyword(text: &str) -> bool {
+    KEYWORDS.contains(&text)
+}
+
+/// Whether an operator following this lexeme is a binary operator
+fn ends_operand(lexeme: &Lexeme, source: &str) -> bool {
+    match lexeme.kind {
+        LexemeKind::Int | LexemeKind::Str => true,
+        LexemeKind::Ident => !KEYWORDS.contains(&lexeme.text(source)),
+        LexemeKind::Punct => matches!(lexeme.text(source), ")" | "]"),
+        LexemeKind::GenericBracket => lexeme.text(source) == ">",
+        LexemeKind::Attribute | LexemeKind::Unknown => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(source: &str) -> Vec<&str> {
+        lex(source).iter().map(|l| l.text(source)).collect()
+    }
+
+    fn binary_operators(source: &str) -> Vec<&str> {
+        lex(source)
+            .iter()
+            .filter(|l| l.binary())
+            .map(|l| l.text(source))
+            .collect()
+    }
+
+    #[test]
+    fn test_lex_without_whitespace() {
+        assert_eq!(texts("x==y"), vec!["x", "==", "y"]);
+        assert_eq!(texts("(a+b)"), vec!["(", "a", "+", "b", ")"]);
+        assert_eq!(
+            binary_operators("assert((x<y)&(a>=b));"),
+            vec!["<", "&", ">="]
+        );
+    }
+
+    #[test]
+    fn test_lex_spans() {
+        let source = "let c = x+y;";
+        let plus = lex(source)
+            .into_iter()
+            .find(|l| l.text(source) == "+")
+            .unwrap();
+        assert_eq!(plus.span(), 9..10);
+    }
+
+    #[test]
+    fn test_lex_comments_and_strings() {
+        let source = r##"
+            // a + b
+            /* a * b /* nested - */ c / d */
+            let s = "a == b \" c != d";
+            let f = f"{a} < {b}";
+            let r = r#"a "&&" b"#;
+            x % y
+        "##;
+        assert_eq!(binary_operators(source), vec!["%"]);
+        assert_eq!(
+            lex(source)
+                .iter()
+                .filter(|l| l.kind() == LexemeKind::Str)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_lex_attributes() {
+        let source = r#"#[test(should_fail_with = "a < b")] fn foo() {}"#;
+        let lexemes = lex(source);
+        assert_eq!(lexemes[0].kind(), LexemeKind::Attribute);
+        assert_eq!(
+            lexemes[0].text(source),
+            r#"#[test(should_fail_with = "a < b")]"#
+        );
+    }
+
+    #[test]
+    fn test_lex_arrows() {
+        assert_eq!(
+            texts("fn f(x: u8) -> u8 { match x { 1 => 2, _ => 3 } }")[7..9],
+            ["->", "u8"]
+        );
+        assert!(binary_operators("fn f(x: u8) -> bool { x => 2 }").is_empty());
+    }
+
+    #[test]
+    fn test_lex_generics() {
+        let source =
+            "fn foo<T, N>(v: Vec<Vec<T>>, a: [u8; N]) -> BoundedVec<T, 4> where T: Eq { a < b }";
+        assert_eq!(binary_operators(source), vec!["<"]);
+
+        let source = "let x: Option<u8>= foo::<u8>(a) > b;";
+        assert_eq!(binary_operators(source), vec![">"]);
+
+        let source =
+            "impl<T> Foo<T> for Bar<T> where T: Eq { fn f(self) -> bool { self.x >> 2 > 1 } }";
+        assert_eq!(binary_operators(source), vec![">>", ">"]);
+    }
+
+    #[test]
+    fn test_lex_prefix_operators() {
+        let source = "let a = -x - !y & *z; foo(&mut b, -1);";
+        assert_eq!(binary_operators(source), vec!["-", "&"]);
+    }
+
+    #[test]
+    fn test_lex_type_annotations() {
+        let source = "fn f(x: &mut [u8; 2], y: u64) { let z: &mut u8 = x & y; }";
+        assert_eq!(binary_operators(source), vec!["&"]);
+    }
+
+    #[test]
+    fn test_lex_struct_literals() {
+        let source =
+            "struct S { a: Field, b: Vec<u8> } fn f() { let s = S { a: x < y, b: c > d }; }";
+        assert_eq!(binary_operators(source), vec!["<", ">"]);
+    }
+
+    #[test]
+    fn test_lex_closures() {
+        let source = "let f = |a: u8, b| a | b; let g = || x * 2;";
+        assert_eq!(binary_operators(source), vec!["|", "*"]);
+    }
+}
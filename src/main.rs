@@ -1,14 +1,20 @@
 use std::io::Result;
 use std::process;
+pub mod candidates;
 pub mod config;
 pub mod file_manager;
+pub mod formats;
 // pub mod filters;
 pub mod handlers;
 pub mod languages;
+pub mod lexer;
+pub mod operators;
 pub mod processor;
 pub mod reporter;
+pub mod syntax;
 pub mod token;
 pub mod utils;
+pub mod workspace;
 
 mod cli;
 
@@ -0,0 +1,685 @@
+//! Which mutation operators are applied, configured with CLI flags and a `hunter.toml` file.
+//!
+//! ```toml
+//! [operators]
+//! # Only mutate these families (default: all of them)
+//! enabled = ["arithmetic", "relational"]
+//! # Never mutate these families
+//! disabled = ["bitwise"]
+//! # Never apply these single substitutions
+//! skip = ["% -> *", "< -> true"]
+//! ```
+
+use crate::{
+    cli::Args,
+    token::{
+        is_int_literal, raw_string_as_token, token_as_bytes, token_replacements, MutationSet, Token,
+    },
+};
+use serde::Deserialize;
+use std::{
+    fmt, fs,
+    io::{Error, Result},
+    path::Path,
+    str::FromStr,
+};
+
+pub const CONFIG_FILE_NAME: &str = "hunter.toml";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperatorFamily {
+    /// `+`, `-`, `*`, `/` and `%`
+    Arithmetic,
+    /// `&`, `|`, `^`, `<<` and `>>`
+    Bitwise,
+    /// `==`, `!=`, `<`, `<=`, `>` and `>=`, including replacing the whole comparison
+    Relational,
+    /// `&&` and `||`
+    Logical,
+    /// `+=`, `-=` and the other compound assignments, `++` and `--`
+    Shorthand,
+    /// Removing `!`
+    Unary,
+    /// Integer and Field literals, replaced with `0`, `1`, `n + 1` and `n - 1`
+    Literal,
+    /// Flipping `true` and `false`
+    Boolean,
+    /// Negating `if` and `assert` conditions, or replacing them with `true` and `false`
+    Condition,
+    /// Deleting `assert`, `assert_eq` and `constrain` statements, see `Mode::Constraints`
+    Constraint,
+    /// Deleting assignment and call statements
+    Statement,
+    /// Replacing a function's return value with its type's default
+    Return,
+    /// Narrowing and widening `as` casts to integer types and `Field`
+    Cast,
+    /// Shifting the bounds of `for` loop ranges by one, and swapping `..` and `..=`
+    Range,
+    /// Tampering with the results of calls to `unconstrained fn`s, see `Mode::Hints`
+    Hint,
+    /// Swapping adjacent call arguments of the same type, and the operands of `-`, `/`, `%`,
+    /// `<<` and `>>`
+    Swap,
+    /// Shifting array indexes by one
+    Index,
+    /// Swapping struct field accesses for another field of the same type
+    Field,
+}
+
+impl OperatorFamily {
+    pub fn all() -> Vec<OperatorFamily> {
+        vec![
+            OperatorFamily::Arithmetic,
+            OperatorFamily::Bitwise,
+            OperatorFamily::Relational,
+            OperatorFamily::Logical,
+            OperatorFamily::Shorthand,
+            OperatorFamily::Unary,
+            OperatorFamily::Literal,
+            OperatorFamily::Boolean,
+            OperatorFamily::Condition,
+            OperatorFamily::Constraint,
+            OperatorFamily::Statement,
+            OperatorFamily::Return,
+            OperatorFamily::Cast,
+            OperatorFamily::Range,
+            OperatorFamily::Hint,
+            OperatorFamily::Swap,
+            OperatorFamily::Index,
+            OperatorFamily::Field,
+        ]
+    }
+
+    pub fn list() -> String {
+        OperatorFamily::all()
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The family an operator belongs to
+    pub fn of(token: &Token) -> Option<OperatorFamily> {
+        match token {
+            Token::Plus | Token::Minus | Token::Star | Token::Slash | Token::Percent => {
+                Some(OperatorFamily::Arithmetic)
+            }
+            Token::Ampersand
+            | Token::Pipe
+            | Token::Caret
+            | Token::ShiftLeft
+            | Token::ShiftRight => Some(OperatorFamily::Bitwise),
+            Token::Equal
+            | Token::NotEqual
+            | Token::Less
+            | Token::LessEqual
+            | Token::Greater
+            | Token::GreaterEqual => Some(OperatorFamily::Relational),
+            Token::DoubleAmpersand | Token::DoublePipe => Some(OperatorFamily::Logical),
+            Token::PlusEquals
+            | Token::MinusEquals
+            | Token::StarEquals
+            | Token::SlashEquals
+            | Token::PercentEquals
+            | Token::AmpersandEquals
+            | Token::PipeEquals
+            | Token::CaretEquals
+            | Token::ShiftLeftEquals
+            | Token::ShiftRightEquals
+            | Token::Increment
+            | Token::Decrement => Some(OperatorFamily::Shorthand),
+            Token::Bang => Some(OperatorFamily::Unary),
+            Token::Literal(_) => Some(OperatorFamily::Literal),
+            Token::True | Token::False => Some(OperatorFamily::Boolean),
+            Token::Condition(_) => Some(OperatorFamily::Condition),
+            Token::Constraint(_) => Some(OperatorFamily::Constraint),
+            Token::Statement(_) => Some(OperatorFamily::Statement),
+            Token::ReturnValue(..) => Some(OperatorFamily::Return),
+            Token::Cast(_) | Token::CastOperand(_) => Some(OperatorFamily::Cast),
+            Token::DotDot | Token::DotDotEqual | Token::RangeBound(_) => {
+                Some(OperatorFamily::Range)
+            }
+            Token::Hint(..) => Some(OperatorFamily::Hint),
+            Token::Swap(..) => Some(OperatorFamily::Swap),
+            Token::Index(_) => Some(OperatorFamily::Index),
+            Token::FieldAccess(..) => Some(OperatorFamily::Field),
+            Token::Void => None,
+        }
+    }
+
+    /// The operators in the family, or samples of the code the family mutates
+    pub fn operators(&self) -> Vec<Token> {
+        match self {
+            OperatorFamily::Literal => return vec![Token::Literal("64".to_string())],
+            OperatorFamily::Boolean => return vec![Token::True, Token::False],
+            OperatorFamily::Condition => return vec![Token::Condition("a == b".to_string())],
+            OperatorFamily::Constraint => {
+                return vec![Token::Constraint("assert(a == b);".to_string())]
+            }
+            OperatorFamily::Statement => return vec![Token::Statement("a = b;".to_string())],
+            OperatorFamily::Return => {
+                return vec![Token::ReturnValue("a + b".to_string(), "Field".to_string())]
+            }
+            OperatorFamily::Cast => {
+                return ["u8", "u32", "u64", "Field"]
+                    .iter()
+                    .map(|t| Token::Cast(t.to_string()))
+                    .chain([Token::CastOperand("u64".to_string())])
+                    .collect()
+            }
+            OperatorFamily::Range => {
+                return vec![
+                    Token::DotDot,
+                    Token::DotDotEqual,
+                    Token::RangeBound("n".to_string()),
+                ]
+            }
+            OperatorFamily::Hint => {
+                return vec![Token::Hint("sqrt_hint(x)".to_string(), "Field".to_string())]
+            }
+            OperatorFamily::Swap => {
+                return vec![
+                    Token::Swap("x, y".to_string(), "y, x".to_string()),
+                    Token::Swap("a - b".to_string(), "b - a".to_string()),
+                ]
+            }
+            OperatorFamily::Index => {
+                return vec![Token::Index("0".to_string()), Token::Index("i".to_string())]
+            }
+            OperatorFamily::Field => {
+                return vec![Token::FieldAccess("x".to_string(), "y".to_string())]
+            }
+            _ => {}
+        }
+
+        crate::token::all_tokens()
+            .into_iter()
+            .filter(|token| OperatorFamily::of(token) == Some(*self))
+            .collect()
+    }
+}
+
+impl fmt::Display for OperatorFamily {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            OperatorFamily::Arithmetic => "arithmetic",
+            OperatorFamily::Bitwise => "bitwise",
+            OperatorFamily::Relational => "relational",
+            OperatorFamily::Logical => "logical",
+            OperatorFamily::Shorthand => "shorthand",
+            OperatorFamily::Unary => "unary",
+            OperatorFamily::Literal => "literal",
+            OperatorFamily::Boolean => "boolean",
+            OperatorFamily::Condition => "condition",
+            OperatorFamily::Constraint => "constraint",
+            OperatorFamily::Statement => "statement",
+            OperatorFamily::Return => "return",
+            OperatorFamily::Cast => "cast",
+            OperatorFamily::Range => "range",
+            OperatorFamily::Hint => "hint",
+            OperatorFamily::Swap => "swap",
+            OperatorFamily::Index => "index",
+            OperatorFamily::Field => "field",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for OperatorFamily {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        OperatorFamily::all()
+            .into_iter()
+            .find(|family| family.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "No matching operator family `{}`. Current supported families are: {}",
+                    s,
+                    OperatorFamily::list()
+                )
+            })
+    }
+}
+
+/// What the mutants are generated to look for
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Mode {
+    /// Operator, literal and condition mutants, measuring how well the tests check behaviour
+    #[default]
+    Standard,
+    /// Deleting constraints one at a time, finding under-constrained code
+    Constraints,
+    /// Tampering with the results of unconstrained calls, finding hints that are never checked
+    Hints,
+}
+
+impl Mode {
+    pub fn list() -> String {
+        ["standard", "constraints", "hints"].join(", ")
+    }
+
+    /// The families the mode mutates, unless narrowed down with `--operators`
+    pub fn families(&self) -> Vec<OperatorFamily> {
+        match self {
+            Mode::Standard => OperatorFamily::all()
+                .into_iter()
+                .filter(|family| {
+                    !matches!(family, OperatorFamily::Constraint | OperatorFamily::Hint)
+                })
+                .collect(),
+            Mode::Constraints => vec![OperatorFamily::Constraint],
+            Mode::Hints => vec![OperatorFamily::Hint],
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(Mode::Standard),
+            "constraints" => Ok(Mode::Constraints),
+            "hints" => Ok(Mode::Hints),
+            _ => Err(format!(
+                "No matching mode. Current supported modes are: {}",
+                Mode::list()
+            )),
+        }
+    }
+}
+
+/// A single replacement of one operator by another, written as i.e: `% -> *`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Substitution {
+    from: Token,
+    to: Token,
+}
+
+impl Substitution {
+    pub fn new(from: Token, to: Token) -> Substitution {
+        Substitution { from, to }
+    }
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}",
+            token_source(&self.from),
+            token_source(&self.to)
+        )
+    }
+}
+
+impl FromStr for Substitution {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let operator = |text: &str| match text {
+            "true" => Some(Token::True),
+            "false" => Some(Token::False),
+            ".." => Some(Token::DotDot),
+            "..=" => Some(Token::DotDotEqual),
+            _ if is_int_literal(text) => Some(Token::Literal(text.to_string())),
+            _ => raw_string_as_token(text),
+        };
+        let replacement = |text: &str| match text {
+            "" | "\"\"" => Some(Token::Void),
+            _ => operator(text),
+        };
+
+        // The operators themselves may contain `->`'s characters, i.e: `--->++`
+        s.match_indices("->")
+            .find_map(|(i, _)| {
+                let from = operator(s[..i].trim())?;
+                let to = replacement(s[i + 2..].trim())?;
+                Some(Substitution { from, to })
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Invalid substitution `{}`, expected i.e: `% -> *`. Run `hunter operators` to list them",
+                    s
+                )
+            })
+    }
+}
+
+/// The operators that are mutated and what they are replaced with
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorConfig {
+    mutation_set: MutationSet,
+    families: Vec<OperatorFamily>,
+    skipped: Vec<Substitution>,
+}
+
+impl Default for OperatorConfig {
+    fn default() -> Self {
+        OperatorConfig {
+            mutation_set: MutationSet::Minimal,
+            families: Mode::Standard.families(),
+            skipped: vec![],
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    operators: OperatorsSection,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct OperatorsSection {
+    enabled: Option<Vec<String>>,
+    #[serde(default)]
+    disabled: Vec<String>,
+    #[serde(default)]
+    skip: Vec<String>,
+}
+
+impl OperatorConfig {
+    pub fn new(
+        mutation_set: MutationSet,
+        families: Vec<OperatorFamily>,
+        skipped: Vec<Substitution>,
+    ) -> OperatorConfig {
+        OperatorConfig {
+            mutation_set,
+            families,
+            skipped,
+        }
+    }
+
+    /// Combines the config file with the CLI flags, which take precedence, for `--mode`.
+    /// The config file is `--config`, or `hunter.toml` in the current directory if it exists.
+    pub fn from_args(args: &Args) -> Result<OperatorConfig> {
+        let file = match &args.config {
+            Some(path) => Some(read_config_file(path)?),
+            None if Path::new(CONFIG_FILE_NAME).is_file() => {
+                Some(read_config_file(Path::new(CONFIG_FILE_NAME))?)
+            }
+            None => None,
+        }
+        .unwrap_or_default();
+
+        let parse_families = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| name.parse::<OperatorFamily>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(Error::other)
+        };
+
+        // Only the standard mode can be narrowed down to some families
+        let mut families = match (args.mode, &args.operators, &file.operators.enabled) {
+            (Mode::Standard, Some(families), _) => families.clone(),
+            (Mode::Standard, None, Some(names)) => parse_families(names)?,
+            (mode, _, _) => mode.families(),
+        };
+        let disabled = parse_families(&file.operators.disabled)?;
+        families.retain(|family| !disabled.contains(family));
+
+        let mut skipped = file
+            .operators
+            .skip
+            .iter()
+            .map(|s| s.parse::<Substitution>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Error::other)?;
+        skipped.extend(args.skip.iter().cloned());
+
+        Ok(OperatorConfig::new(args.mutation_set, families, skipped))
+    }
+
+    /// Whether the operator's family is enabled
+    pub fn enables(&self, token: &Token) -> bool {
+        OperatorFamily::of(token).is_some_and(|family| self.families.contains(&family))
+    }
+
+    /// Whether replacing `original` with `mutation` is enabled
+    pub fn allows(&self, original: &Token, mutation: &Token) -> bool {
+        self.enables(original)
+            && !self
+                .skipped
+                .contains(&Substitution::new(original.clone(), mutation.clone()))
+    }
+
+    /// The enabled replacements of an operator
+    pub fn replacements(&self, original: &Token) -> Vec<Token> {
+        token_replacements(original, self.mutation_set)
+            .into_iter()
+            .filter(|mutation| self.allows(original, mutation))
+            .collect()
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<ConfigFile> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        Error::new(
+            e.kind(),
+            format!("Unable to read config file {}: {}", path.display(), e),
+        )
+    })?;
+    toml::from_str(&contents)
+        .map_err(|e| Error::other(format!("Invalid config file {}: {}", path.display(), e)))
+}
+
+/// The source text of an operator, i.e: `%`
+pub fn token_source(token: &Token) -> String {
+    match token {
+        Token::Void => "\"\"".to_string(),
+        _ => String::from_utf8_lossy(token_as_bytes(token).unwrap_or_default()).into_owned(),
+    }
+}
+
+/// An example of the operator in use, i.e: `a % b`
+pub fn example(token: &Token) -> String {
+    let operator = token_source(token);
+    match token {
+        Token::Bang => "!a".to_string(),
+        Token::Increment | Token::Decrement => format!("a{}", operator),
+        Token::True | Token::False => format!("let a = {}", operator),
+        Token::Literal(_) => format!("a < {}", operator),
+        Token::Condition(_) => format!("if {} {{ .. }}", operator),
+        Token::Constraint(_) | Token::Statement(_) => format!("{{ {} a + b }}", operator),
+        Token::Cast(_) => format!("a as {}", operator),
+        Token::CastOperand(_) => format!("a as {} > b", operator),
+        Token::DotDot | Token::DotDotEqual => format!("for i in 0{}n {{ .. }}", operator),
+        Token::RangeBound(_) => format!("for i in 0..{} {{ .. }}", operator),
+        Token::ReturnValue(_, return_type) => {
+            format!("fn f() -> {} {{ {} }}", return_type, operator)
+        }
+        Token::Hint(..) => format!("let r = {};", operator),
+        Token::Swap(text, _) if text.contains(',') => format!("f({})", operator),
+        Token::Swap(..) => operator,
+        Token::Index(_) => format!("arr[{}]", operator),
+        Token::FieldAccess(..) => format!("p.{}", operator),
+        Token::Void => String::new(),
+        _ => format!("a {} b", operator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::discriminant;
+
+    #[test]
+    fn test_substitution_from_str() {
+        assert_eq!(
+            "% -> *".parse::<Substitution>(),
+            Ok(Substitution::new(Token::Percent, Token::Star))
+        );
+        assert_eq!(
+            "--->++".parse::<Substitution>(),
+            Ok(Substitution::new(Token::Decrement, Token::Increment))
+        );
+        assert_eq!(
+            "-=->+=".parse::<Substitution>(),
+            Ok(Substitution::new(Token::MinusEquals, Token::PlusEquals))
+        );
+        assert_eq!(
+            ".. -> ..=".parse::<Substitution>(),
+            Ok(Substitution::new(Token::DotDot, Token::DotDotEqual))
+        );
+        assert_eq!(
+            "< -> true".parse::<Substitution>(),
+            Ok(Substitution::new(Token::Less, Token::True))
+        );
+        assert_eq!(
+            "! -> \"\"".parse::<Substitution>(),
+            Ok(Substitution::new(Token::Bang, Token::Void))
+        );
+        assert_eq!(
+            "64 -> 65".parse::<Substitution>(),
+            Ok(Substitution::new(
+                Token::Literal("64".to_string()),
+                Token::Literal("65".to_string())
+            ))
+        );
+        assert!("% * ".parse::<Substitution>().is_err());
+        assert!("% -> x".parse::<Substitution>().is_err());
+    }
+
+    #[test]
+    fn test_operator_config_allows() {
+        let config = OperatorConfig::new(
+            MutationSet::Exhaustive,
+            vec![OperatorFamily::Arithmetic, OperatorFamily::Relational],
+            vec![Substitution::new(Token::Percent, Token::Star)],
+        );
+        assert!(config.allows(&Token::Plus, &Token::Minus));
+        assert!(!config.allows(&Token::Percent, &Token::Star));
+        assert!(config.allows(&Token::Percent, &Token::Plus));
+        assert!(!config.allows(&Token::Ampersand, &Token::Pipe));
+        assert_eq!(
+            config.replacements(&Token::Percent),
+            vec![Token::Plus, Token::Minus, Token::Slash]
+        );
+        assert!(config.replacements(&Token::Bang).is_empty());
+    }
+
+    #[test]
+    fn test_mode_families() {
+        assert!(!Mode::Standard
+            .families()
+            .contains(&OperatorFamily::Constraint));
+        assert_eq!(
+            Mode::Constraints.families(),
+            vec![OperatorFamily::Constraint]
+        );
+        assert_eq!("Constraints".parse::<Mode>(), Ok(Mode::Constraints));
+        assert_eq!("hints".parse::<Mode>(), Ok(Mode::Hints));
+        assert_eq!(Mode::Hints.families(), vec![OperatorFamily::Hint]);
+        assert!(!Mode::Standard.families().contains(&OperatorFamily::Hint));
+        assert!("fuzz".parse::<Mode>().is_err());
+
+        let config = OperatorConfig::default();
+        let constraint = Token::Constraint("assert(a == b);".to_string());
+        assert!(!config.enables(&constraint));
+        let config =
+            OperatorConfig::new(MutationSet::Minimal, Mode::Constraints.families(), vec![]);
+        assert_eq!(config.replacements(&constraint), vec![Token::Void]);
+        assert!(!config.enables(&Token::Plus));
+    }
+
+    #[test]
+    fn test_config_file() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            [operators]
+            disabled = ["bitwise"]
+            skip = ["% -> *"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file.operators.enabled, None);
+        assert_eq!(file.operators.disabled, vec!["bitwise"]);
+        assert!(toml::from_str::<ConfigFile>("[operators]\nenable = []").is_err());
+    }
+
+    #[test]
+    fn test_every_operator_has_a_family() {
+        use OperatorFamily::*;
+        let code = || "a".to_string();
+        let expected = [
+            (Token::Plus, Some(Arithmetic)),
+            (Token::Minus, Some(Arithmetic)),
+            (Token::Star, Some(Arithmetic)),
+            (Token::Slash, Some(Arithmetic)),
+            (Token::Percent, Some(Arithmetic)),
+            (Token::Ampersand, Some(Bitwise)),
+            (Token::Pipe, Some(Bitwise)),
+            (Token::Caret, Some(Bitwise)),
+            (Token::ShiftLeft, Some(Bitwise)),
+            (Token::ShiftRight, Some(Bitwise)),
+            (Token::Less, Some(Relational)),
+            (Token::LessEqual, Some(Relational)),
+            (Token::Greater, Some(Relational)),
+            (Token::GreaterEqual, Some(Relational)),
+            (Token::Equal, Some(Relational)),
+            (Token::NotEqual, Some(Relational)),
+            (Token::DoublePipe, Some(Logical)),
+            (Token::DoubleAmpersand, Some(Logical)),
+            (Token::PlusEquals, Some(Shorthand)),
+            (Token::MinusEquals, Some(Shorthand)),
+            (Token::StarEquals, Some(Shorthand)),
+            (Token::SlashEquals, Some(Shorthand)),
+            (Token::PercentEquals, Some(Shorthand)),
+            (Token::AmpersandEquals, Some(Shorthand)),
+            (Token::PipeEquals, Some(Shorthand)),
+            (Token::CaretEquals, Some(Shorthand)),
+            (Token::ShiftLeftEquals, Some(Shorthand)),
+            (Token::ShiftRightEquals, Some(Shorthand)),
+            (Token::Increment, Some(Shorthand)),
+            (Token::Decrement, Some(Shorthand)),
+            (Token::Bang, Some(Unary)),
+            (Token::Literal(code()), Some(Literal)),
+            (Token::True, Some(Boolean)),
+            (Token::False, Some(Boolean)),
+            (Token::Condition(code()), Some(Condition)),
+            (Token::Constraint(code()), Some(Constraint)),
+            (Token::Statement(code()), Some(Statement)),
+            (Token::ReturnValue(code(), code()), Some(Return)),
+            (Token::Cast(code()), Some(Cast)),
+            (Token::CastOperand(code()), Some(Cast)),
+            (Token::DotDot, Some(Range)),
+            (Token::DotDotEqual, Some(Range)),
+            (Token::RangeBound(code()), Some(Range)),
+            (Token::Hint(code(), code()), Some(Hint)),
+            (Token::Swap(code(), code()), Some(Swap)),
+            (Token::Index(code()), Some(Index)),
+            (Token::FieldAccess(code(), code()), Some(Field)),
+            (Token::Void, None),
+        ];
+
+        for (token, family) in expected {
+            assert_eq!(OperatorFamily::of(&token), family, "{:?}", token);
+
+            // The family lists the operator, or a sample of the same kind
+            if let Some(family) = family {
+                assert!(
+                    family
+                        .operators()
+                        .iter()
+                        .any(|operator| discriminant(operator) == discriminant(&token)),
+                    "{:?} is missing from {}",
+                    token,
+                    family
+                );
+            }
+        }
+
+        for family in OperatorFamily::all() {
+            for operator in family.operators() {
+                assert_eq!(OperatorFamily::of(&operator), Some(family));
+            }
+        }
+    }
+}
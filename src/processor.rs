@@ -3,10 +3,8 @@ use std::{
     fs,
     path::PathBuf,
     process,
-    sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc, Mutex,
-    },
+    sync::{Mutex, Once},
+    time::{Duration, Instant},
 };
 
 use ctrlc;
@@ -14,115 +12,115 @@ use lazy_static::lazy_static;
 use rayon::prelude::*;
 
 use crate::{
-    cli::Args,
     config::LanguageConfig,
     file_manager::mutate_temp_file,
-    handlers::mutator::{calculate_mutation_score, Mutant, MutationStatus},
-    languages::common::Language,
-    reporter::{mutants_progress_bar, mutation_test_summary_table, print_table},
+    handlers::{
+        baseline::test_outcomes,
+        mutator::{Mutant, MutationStatus},
+    },
+    reporter::mutants_progress_bar,
+    workspace::WorkspacePool,
 };
 
+const DIAGNOSTIC_LINES: usize = 4;
+
 pub fn process_mutants(
     mutants: &mut Vec<Mutant>,
-    args: Args,
     config: Box<dyn LanguageConfig + Send + Sync>,
+    build_timeout: Option<Duration>,
+    test_timeout: Option<Duration>,
 ) {
-    // Handle the Ctrl+C interrupt signal
-    ctrlc::set_handler(move || {
-        let temp_dirs = TEMP_DIRS.lock().unwrap();
-
-        // Delete all temporary directories
-        for path in temp_dirs.iter() {
-            let _ = fs::remove_dir_all(path);
-        }
+    // Handle the Ctrl+C interrupt signal; the handler can only be set once per process, and
+    // higher-order mutants are processed in a second run
+    static CTRLC: Once = Once::new();
+    CTRLC.call_once(|| {
+        ctrlc::set_handler(move || {
+            let temp_dirs = TEMP_DIRS.lock().unwrap();
+
+            // Delete all temporary directories
+            for path in temp_dirs.iter() {
+                let _ = fs::remove_dir_all(path);
+            }
 
-        std::process::exit(0);
-    })
-    .expect("Error setting Ctrl-C handler");
+            std::process::exit(0);
+        })
+        .expect("Error setting Ctrl-C handler");
+    });
 
-    let original_dir = std::env::current_dir().unwrap();
     let total_mutants = mutants.len();
     let bar = mutants_progress_bar(total_mutants);
 
-    // mutatant status counters
-    let destroyed = Arc::new(AtomicUsize::new(0));
-    let survived = Arc::new(AtomicUsize::new(0));
-    let unbuildable = Arc::new(AtomicUsize::new(0));
-    let pending = Arc::new(AtomicUsize::new(total_mutants));
-
     lazy_static! {
         static ref TEMP_DIRS: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
     }
 
-    lazy_static! {
-        static ref LIB_FILE_MUTEX: Mutex<()> = Mutex::new(());
-    }
-
-    let temp_dir = config
-        .setup_test_infrastructure()
+    // One workspace per worker thread, so each mutant is built and tested in isolation
+    let pool = WorkspacePool::new(rayon::current_num_threads(), &*config)
         .expect("Failed to setup test infrastructure");
 
     // Add the paths of the temporary directories to the global variable
-    TEMP_DIRS
-        .lock()
-        .unwrap()
-        .insert(temp_dir.path().to_path_buf());
-
-    let config = Arc::new(Mutex::new(config));
+    TEMP_DIRS.lock().unwrap().extend(pool.paths());
 
     mutants.par_iter_mut().for_each(|m| {
-        let config = Arc::clone(&config);
-        let config_guard = config.lock().unwrap();
-
         // Check if the source file exists
         if !m.path().exists() {
             eprint!("Source File does not exist. Shutting down...");
             std::process::exit(1);
         }
 
-        let lib_mutex = match config_guard.language() {
-            Language::Noir => Some(&LIB_FILE_MUTEX as &Mutex<()>),
-        };
-
-        let temp_file = config_guard.copy_src_file(&temp_dir, m, lib_mutex)
-            .expect("Failed to copy src to temp file");
+        let mut workspace = pool.checkout();
 
-        mutate_temp_file(&temp_file, m);
+        let temp_file = workspace.prepare(&*config, m).unwrap_or_else(|e| {
+            eprintln!("Failed to prepare the mutant's workspace: {}", e);
+            std::process::exit(1);
+        });
 
-        // set current dir to "./temp"
-        if let Err(e) = std::env::set_current_dir(temp_dir.as_ref()) {
-            eprintln!("Failed to change to the temporary directory: {}", e);
+        if let Err(e) = mutate_temp_file(&temp_file, m) {
+            // i.e: the source file changed since it was scanned
+            m.set_diagnostic(format!("Failed to apply the mutation: {}", e));
+            bar.inc(1);
+            return;
         }
-
-        let build_output = config_guard.build_mutant_project();
-        let test_output = config_guard.test_mutant_project();
+        let start = Instant::now();
+
+        // Commands run in the mutant's workspace; the process-wide cwd is never touched
+        let Some(build_output) = config.build_mutant_project(workspace.path(), build_timeout)
+        else {
+            // i.e: a mutated loop bound that the compiler unrolls forever
+            m.set_duration(start.elapsed());
+            m.set_status(MutationStatus::Timeout);
+            bar.inc(1);
+            return;
+        };
         let build_status = build_output.status.code();
-        let test_status = test_output.status.code();
-
 
         match build_status {
             Some(0) => {
-                match test_status {
-                    Some(0) => {
-                        m.set_status(MutationStatus::Survived);
-                        survived.fetch_add(1, Ordering::SeqCst);
-                        pending.fetch_sub(1, Ordering::SeqCst);
-                    }
-                    Some(_) => {
-                        destroyed.fetch_add(1, Ordering::SeqCst);
-                        pending.fetch_sub(1, Ordering::SeqCst);
-                        m.set_status(MutationStatus::Killed);
-                    }
-                    None => {
+                let test_output = config.test_mutant_project(workspace.path(), test_timeout);
+                m.set_duration(start.elapsed());
+
+                if let Some(killed_by) = test_output.as_ref().and_then(|output| {
+                    test_outcomes(output, &*config)
+                        .into_iter()
+                        .find(|(_, passed)| !passed)
+                }) {
+                    m.set_killed_by(killed_by.0);
+                }
+
+                match test_output.map(|output| output.status.code()) {
+                    Some(Some(0)) => m.set_status(MutationStatus::Survived),
+                    Some(Some(_)) => m.set_status(MutationStatus::Killed),
+                    Some(None) => {
                         eprintln!("Test suite was killed by a signal or crashed");
                         process::exit(1);
                     }
+                    None => m.set_status(MutationStatus::Timeout),
                 }
             }
             Some(_) => {
-                unbuildable.fetch_add(1, Ordering::SeqCst);
-                pending.fetch_sub(1, Ordering::SeqCst);
-                m.set_status(MutationStatus::Killed);
+                m.set_duration(start.elapsed());
+                m.set_status(MutationStatus::Unbuildable);
+                m.set_diagnostic(compiler_diagnostic(&build_output));
             }
             None => {
                     eprintln!("Build was killed by a signal or crashed");
@@ -132,26 +130,23 @@ pub fn process_mutants(
         }
 
         bar.inc(1);
-        if let Err(e) = std::env::set_current_dir(&original_dir) {
-            eprintln!("Failed to change back to the original directory: {}", e);
-        }
     });
 
     bar.finish_with_message("All mutants processed!");
+}
 
-    let score = calculate_mutation_score(
-        destroyed.load(Ordering::SeqCst) as f64,
-        unbuildable.load(Ordering::SeqCst) as f64,
-        total_mutants as f64,
-    );
-    let summary_table = mutation_test_summary_table(
-        total_mutants as f64,
-        pending.load(Ordering::SeqCst) as f64,
-        unbuildable.load(Ordering::SeqCst) as f64,
-        destroyed.load(Ordering::SeqCst) as f64,
-        survived.load(Ordering::SeqCst) as f64,
-        score,
-    );
-
-    print_table(args.output_path, summary_table).unwrap();
+/// The first few lines of the compiler error, enough to tell why a mutant didn't build
+fn compiler_diagnostic(build_output: &process::Output) -> String {
+    let stderr = String::from_utf8_lossy(&build_output.stderr);
+    let text = if stderr.trim().is_empty() {
+        String::from_utf8_lossy(&build_output.stdout)
+    } else {
+        stderr
+    };
+
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(DIAGNOSTIC_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
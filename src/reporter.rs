@@ -1,90 +1,113 @@
 use crate::{
     config::LanguageConfig,
+    formats::{line_column, token_text},
     handlers::{
+        baseline::Baseline,
         mutator::{Mutant, MutationStatus},
         scanner::ScanResult,
     },
-    token::{token_as_bytes, Token},
+    operators::{example, token_source, OperatorFamily},
+    token::{token_as_bytes, token_replacements, MutationSet, Token},
 };
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use prettytable::{Cell, Row, Table};
 use regex::Regex;
 use std::{
+    fmt::Display,
     fs::{File, OpenOptions},
     io::{BufRead, BufReader, Read, Result},
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
-pub fn print_scan_results(results: &mut ScanResult, config: Box<dyn LanguageConfig>) -> Result<()> {
-    println!("{}", "Initiating source file analysis...".green());
+static PROGRESS_TO_STDERR: AtomicBool = AtomicBool::new(false);
 
-    println!(
-        "{}",
-        format!("Searching for {} files", config.name()).green()
-    );
+/// Sends progress messages to stderr instead of stdout, so that a report written to stdout
+/// can be piped without them
+pub fn progress_to_stderr(enabled: bool) {
+    PROGRESS_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
 
-    println!(
-        "{}",
-        format!("Files found: {}", results.paths().len()).cyan()
-    );
+/// Prints a progress message to stdout, or to stderr if `progress_to_stderr` is set
+pub fn progress(message: impl Display) {
+    if PROGRESS_TO_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+pub fn print_scan_results(
+    results: &mut ScanResult,
+    config: Box<dyn LanguageConfig>,
+    baseline: Option<&Baseline>,
+) -> Result<()> {
+    progress("Initiating source file analysis...".green());
+
+    progress(format!("Searching for {} files", config.name()).green());
+
+    progress(format!("Files found: {}", results.paths().len()).cyan());
 
     let noir_files_without_unit_tests = results.paths().len() - results.contains_unit_tests().len();
 
     if config.filter_tests() {
-        println!("Hunter currently only mutates files containing unit tests.");
+        progress("Hunter currently only mutates files containing unit tests.");
 
-        println!(
-            "{}",
+        progress(
             format!(
                 "Skipping {} {} files.",
                 noir_files_without_unit_tests,
                 config.name(),
             )
-            .magenta()
+            .magenta(),
         );
 
-        println!(
-            "{}",
+        progress(
             format!(
                 "{} files containing unit tests: {}",
                 config.name(),
                 results.contains_unit_tests().len()
             )
-            .cyan()
+            .cyan(),
         );
 
         for path in results.contains_unit_tests() {
-            println!("{}", format!("{}", path.display()).red());
+            progress(format!("{}", path.display()).red());
         }
     } else {
-        println!(
-            "{}",
-            format!("Hunter will mutate all {} files found.", config.name()).magenta()
-        );
+        progress(format!("Hunter will mutate all {} files found.", config.name()).magenta());
 
-        println!(
-            "{}",
-            format!("{} files found: {}", config.name(), results.paths().len()).cyan()
-        );
+        progress(format!("{} files found: {}", config.name(), results.paths().len()).cyan());
 
         for path in results.paths() {
-            println!("{}", format!("{}", path.display()).red());
+            progress(format!("{}", path.display()).red());
         }
     }
 
-    println!("{}", "Collecting tokens from files".green());
-    println!("{}", "Analysing tokens".green());
+    progress("Collecting tokens from files".green());
+    progress("Analysing tokens".green());
 
     let num_mutants: usize = results.mutants().len();
-    println!(
-        "{}",
-        format!("Mutable tokens found: {}", num_mutants).cyan()
-    );
-    println!(
-        "{}",
-        format!("tests to run: {}", num_mutants * results.test_count()).magenta()
-    );
+    progress(format!("Mutable tokens found: {}", num_mutants).cyan());
+    progress(format!("tests to run: {}", num_mutants * results.test_count()).magenta());
+
+    if let Some(baseline) = baseline {
+        if let Some((test, duration)) = baseline.test_durations().first() {
+            progress(format!("Slowest test: {} ({:.2?})", test, duration).magenta());
+        }
+
+        // Each mutant costs a build and test run of its own project, and mutants are built and
+        // tested in parallel, one per worker thread
+        let total: Duration = results
+            .mutants()
+            .iter()
+            .map(|mutant| baseline.mutant_duration(mutant, &*config))
+            .sum();
+        let eta = total / rayon::current_num_threads().max(1) as u32;
+        progress(format!("Estimated time to completion: {:.0?}", eta).magenta());
+    }
 
     Ok(())
 }
@@ -93,6 +116,7 @@ pub fn mutation_test_summary_table(
     total_mutants: f64,
     pending: f64,
     unbuildable: f64,
+    timed_out: f64,
     killed: f64,
     survived: f64,
     mutation_score_string: String,
@@ -111,6 +135,12 @@ pub fn mutation_test_summary_table(
         0.0
     };
 
+    let percentage_timed_out = if total_mutants > 0.0 {
+        (timed_out / total_mutants) * 100.0
+    } else {
+        0.0
+    };
+
     let percentage_killed = if total_mutants > 0.0 {
         (killed / total_mutants) * 100.0
     } else {
@@ -143,6 +173,11 @@ pub fn mutation_test_summary_table(
         Cell::new(&unbuildable.to_string()).style_spec("Fcb"),
         Cell::new(&format!("{:.2}%", percentage_unbuildable)).style_spec("Fcb"),
     ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Mutants Timed Out:").style_spec("Fyb"),
+        Cell::new(&timed_out.to_string()).style_spec("Fyb"),
+        Cell::new(&format!("{:.2}%", percentage_timed_out)).style_spec("Fcb"),
+    ]));
     table.add_row(Row::new(vec![
         Cell::new("Mutants Killed:").style_spec("Fgb"),
         Cell::new(&killed.to_string()).style_spec("Fgb"),
@@ -169,12 +204,16 @@ pub fn surviving_mutants_table(mutants: &mut [Mutant]) -> Table {
     table.add_row(Row::new(vec![
         Cell::new("Source file:").style_spec("Fcb"),
         Cell::new("Line #:").style_spec("Fcb"),
+        Cell::new("Function:").style_spec("Fcb"),
         Cell::new("Original context:").style_spec("Fcb"),
         Cell::new("Mutation:").style_spec("Fmb"),
     ]));
 
     for mutant in mutants {
-        if mutant.status() == MutationStatus::Survived || mutant.status() == MutationStatus::Pending
+        if (mutant.status() == MutationStatus::Survived
+            || mutant.status() == MutationStatus::Pending)
+            && !is_under_constrained(mutant)
+            && !is_unverified_hint(mutant)
         {
             let span = mutant.span();
             let span_usize = (span.0 as usize, span.1 as usize);
@@ -182,26 +221,195 @@ pub fn surviving_mutants_table(mutants: &mut [Mutant]) -> Table {
                 &mut table,
                 Path::new(mutant.path()),
                 span_usize,
-                &mutant.mutation(),
+                mutant.function(),
+                &replacements(mutant),
+            )
+            .unwrap();
+        }
+    }
+
+    table
+}
+
+/// Whether the mutant is a deleted constraint that no test noticed
+pub fn is_under_constrained(mutant: &Mutant) -> bool {
+    matches!(mutant.token(), Token::Constraint(_))
+        && mutant.order() == 1
+        && mutant.status() == MutationStatus::Survived
+}
+
+/// Constraints that could be deleted without any test failing
+pub fn under_constrained_table(mutants: &mut [Mutant]) -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Potentially Under-constrained").style_spec("Frb")
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Source file:").style_spec("Fcb"),
+        Cell::new("Line #:").style_spec("Fcb"),
+        Cell::new("Function:").style_spec("Fcb"),
+        Cell::new("Deleted constraint:").style_spec("Frb"),
+    ]));
+
+    for mutant in mutants.iter().filter(|m| is_under_constrained(m)) {
+        let Token::Constraint(constraint) = mutant.token() else {
+            continue;
+        };
+        let source = std::fs::read_to_string(mutant.path()).unwrap_or_default();
+        let (line, _) = line_column(&source, mutant.span_start() as usize);
+        table.add_row(Row::new(vec![
+            Cell::new(&mutant.path().display().to_string()).style_spec("Fb"),
+            Cell::new(&line.to_string()).style_spec("Fb"),
+            Cell::new(mutant.function().unwrap_or("-")).style_spec("Fb"),
+            Cell::new(&constraint).style_spec("Fyb"),
+        ]));
+    }
+
+    table
+}
+
+/// Whether the mutant tampers with an unconstrained call's result without any test noticing
+pub fn is_unverified_hint(mutant: &Mutant) -> bool {
+    matches!(mutant.token(), Token::Hint(..))
+        && mutant.order() == 1
+        && mutant.status() == MutationStatus::Survived
+}
+
+/// Unconstrained calls whose results could be changed without any test failing
+pub fn unverified_hints_table(mutants: &mut [Mutant]) -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Unverified Hints").style_spec("Frb")
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Source file:").style_spec("Fcb"),
+        Cell::new("Line #:").style_spec("Fcb"),
+        Cell::new("Function:").style_spec("Fcb"),
+        Cell::new("Unconstrained call:").style_spec("Fcb"),
+        Cell::new("Replaced with:").style_spec("Frb"),
+    ]));
+
+    for mutant in mutants.iter().filter(|m| is_unverified_hint(m)) {
+        let Token::Hint(call, _) = mutant.token() else {
+            continue;
+        };
+        let source = std::fs::read_to_string(mutant.path()).unwrap_or_default();
+        let (line, _) = line_column(&source, mutant.span_start() as usize);
+        table.add_row(Row::new(vec![
+            Cell::new(&mutant.path().display().to_string()).style_spec("Fb"),
+            Cell::new(&line.to_string()).style_spec("Fb"),
+            Cell::new(mutant.function().unwrap_or("-")).style_spec("Fb"),
+            Cell::new(&call).style_spec("Fyb"),
+            Cell::new(&token_text(&mutant.mutation())).style_spec("Fmb"),
+        ]));
+    }
+
+    table
+}
+
+pub fn unbuildable_mutants_table(mutants: &mut [Mutant]) -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Unbuildable Mutants").style_spec("Fcb")
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Source file:").style_spec("Fcb"),
+        Cell::new("Line #:").style_spec("Fcb"),
+        Cell::new("Function:").style_spec("Fcb"),
+        Cell::new("Original context:").style_spec("Fcb"),
+        Cell::new("Mutation:").style_spec("Fmb"),
+        Cell::new("Compiler error:").style_spec("Frb"),
+    ]));
+
+    for mutant in mutants {
+        if mutant.status() == MutationStatus::Unbuildable {
+            let span = mutant.span();
+            let span_usize = (span.0 as usize, span.1 as usize);
+            let rows = table.len();
+            add_cells_to_table(
+                &mut table,
+                Path::new(mutant.path()),
+                span_usize,
+                mutant.function(),
+                &replacements(mutant),
             )
             .unwrap();
+
+            if table.len() == rows {
+                continue;
+            }
+            if let Some(row) = table.get_mut_row(rows) {
+                row.add_cell(Cell::new(mutant.diagnostic().unwrap_or_default()).style_spec("Fr"));
+            }
+        }
+    }
+
+    table
+}
+
+/// Every mutation operator by family, with what it is replaced with in each mutation set
+pub fn operators_table() -> Table {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Mutation Operators").style_spec("Fcb")
+    ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Family:").style_spec("Fcb"),
+        Cell::new("Operator:").style_spec("Fcb"),
+        Cell::new("Minimal:").style_spec("Fmb"),
+        Cell::new("Exhaustive:").style_spec("Fmb"),
+        Cell::new("Example:").style_spec("Fyb"),
+    ]));
+
+    let sources = |tokens: &[Token]| {
+        tokens
+            .iter()
+            .map(token_source)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    for family in OperatorFamily::all() {
+        for operator in family.operators() {
+            let minimal = token_replacements(&operator, MutationSet::Minimal);
+            let exhaustive = token_replacements(&operator, MutationSet::Exhaustive);
+            let example = match minimal.first() {
+                Some(mutation) => format!("{}  ->  {}", example(&operator), example(mutation)),
+                None => example(&operator),
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(&family.to_string()).style_spec("Fb"),
+                Cell::new(&token_source(&operator)).style_spec("Fcb"),
+                Cell::new(&sources(&minimal)).style_spec("Fm"),
+                Cell::new(&sources(&exhaustive)).style_spec("Fm"),
+                Cell::new(&example).style_spec("Fy"),
+            ]));
         }
     }
 
     table
 }
 
+/// What the mutant replaces its spans with, one replacement per edit
+fn replacements(mutant: &Mutant) -> String {
+    mutant
+        .edits()
+        .iter()
+        .map(|edit| String::from_utf8_lossy(token_as_bytes(edit.mutation()).unwrap()).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ; ")
+}
+
 pub fn add_cells_to_table(
     table: &mut Table,
     file_path: &Path,
     span: (usize, usize),
-    token: &Token,
+    function: Option<&str>,
+    mutation: &str,
 ) -> Result<()> {
     let file = File::open(file_path).unwrap();
     let reader = BufReader::new(file);
     let mut byte_index = 0;
-    let temp = String::from_utf8_lossy(token_as_bytes(&token.clone()).unwrap());
-    let token_representation = temp.as_ref();
 
     for (index, line) in reader.lines().enumerate() {
         let line = line?;
@@ -213,8 +421,9 @@ pub fn add_cells_to_table(
             table.add_row(Row::new(vec![
                 Cell::new(file_path.to_str().unwrap()).style_spec("Fb"),
                 Cell::new(&(index + 1).to_string()).style_spec("Fb"),
+                Cell::new(function.unwrap_or("-")).style_spec("Fb"),
                 Cell::new(&short_line).style_spec("Fcb"),
-                Cell::new(token_representation).style_spec("Fyb"),
+                Cell::new(mutation).style_spec("Fyb"),
             ]));
             break;
         }
@@ -0,0 +1,1634 @@
+//! A lightweight syntax tree of a Noir source file.
+//!
+//! Only items are parsed: modules, functions, impls, traits and `comptime` blocks. That is enough
+//! to tell which function a mutation candidate is in, its item path, and whether it is test code.
+
+use crate::{
+    lexer::{is_keyword, Lexeme, LexemeKind},
+    token::is_int_type,
+};
+use std::ops::Range;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ItemKind {
+    /// An inline module, i.e: `mod math { ... }`
+    Module,
+    /// A function or method, i.e: `fn add(...) { ... }`
+    Function,
+    /// An impl block, named after the type it implements, i.e: `impl Eq for Point { ... }`
+    Impl,
+    /// A trait definition with default methods, i.e: `trait Eq { ... }`
+    Trait,
+    /// A block evaluated at compile time, i.e: `comptime { ... }`
+    Comptime,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Item {
+    kind: ItemKind,
+    name: String,
+    // From the item's first attribute or modifier to its closing brace
+    span: Range<usize>,
+    test: bool,
+    // For functions, whether it is an `unconstrained fn`
+    unconstrained: bool,
+    // For functions, the types of the parameters other than `self`, i.e: `[Field; 2]`
+    params: Vec<String>,
+    // For functions, the return type, i.e: `Field` in `fn f() -> pub Field`
+    return_type: Option<String>,
+    // For functions, the tail expression of the body, i.e: `a + b` in `{ let a = 1; a + b }`
+    tail: Option<Range<usize>>,
+    children: Vec<Item>,
+}
+
+impl Item {
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Whether the item is a `#[test]` function
+    pub fn is_test(&self) -> bool {
+        self.test
+    }
+
+    /// Whether the item is an `unconstrained fn`
+    pub fn is_unconstrained(&self) -> bool {
+        self.unconstrained
+    }
+
+    /// The types of the function's parameters, leaving out `self`
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+
+    /// The function's return type, without its visibility, i.e: `Field` in `-> pub Field`
+    pub fn return_type(&self) -> Option<&str> {
+        self.return_type.as_deref()
+    }
+
+    /// The span of the function body's tail expression, the value it returns
+    pub fn tail(&self) -> Option<Range<usize>> {
+        self.tail.clone()
+    }
+
+    pub fn children(&self) -> &[Item] {
+        &self.children
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SyntaxTree {
+    items: Vec<Item>,
+}
+
+impl SyntaxTree {
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// The items containing the byte offset, outermost first
+    pub fn enclosing(&self, offset: usize) -> Vec<&Item> {
+        let mut path = vec![];
+        let mut items = &self.items;
+        while let Some(item) = items.iter().find(|item| item.span.contains(&offset)) {
+            path.push(item);
+            items = &item.children;
+        }
+        path
+    }
+
+    /// The innermost function containing the byte offset
+    pub fn enclosing_function(&self, offset: usize) -> Option<&Item> {
+        self.enclosing(offset)
+            .into_iter()
+            .rev()
+            .find(|item| item.kind == ItemKind::Function)
+    }
+
+    /// Whether the byte offset is inside a `#[test]` function
+    pub fn in_test(&self, offset: usize) -> bool {
+        self.enclosing(offset).iter().any(|item| item.test)
+    }
+
+    /// The path of the innermost named item containing the byte offset, i.e: `math::Point::add`.
+    /// `module_path` is the path of the file's module, i.e: `["my_crate", "math"]`.
+    pub fn item_path(&self, module_path: &[String], offset: usize) -> String {
+        module_path
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.enclosing(offset)
+                    .into_iter()
+                    .filter(|item| item.kind != ItemKind::Comptime)
+                    .map(Item::name),
+            )
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// All functions and methods in the file
+    pub fn functions(&self) -> Vec<&Item> {
+        fn collect<'a>(items: &'a [Item], functions: &mut Vec<&'a Item>) {
+            for item in items {
+                if item.kind == ItemKind::Function {
+                    functions.push(item);
+                }
+                collect(&item.children, functions);
+            }
+        }
+
+        let mut functions = vec![];
+        collect(&self.items, &mut functions);
+        functions
+    }
+
+    /// All `#[test]` functions in the file
+    pub fn test_functions(&self) -> Vec<&Item> {
+        self.functions()
+            .into_iter()
+            .filter(|item| item.test)
+            .collect()
+    }
+}
+
+/// Parses the items of a Noir source file from its lexemes.
+pub fn parse(source: &str, lexemes: &[Lexeme]) -> SyntaxTree {
+    let parser = Parser { source, lexemes };
+    SyntaxTree {
+        items: parser.items(0, lexemes.len()),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    lexemes: &'a [Lexeme],
+}
+
+impl Parser<'_> {
+    fn text(&self, i: usize) -> &str {
+        self.lexemes.get(i).map_or("", |l| l.text(self.source))
+    }
+
+    fn is_punct(&self, i: usize, punct: &str) -> bool {
+        self.lexemes
+            .get(i)
+            .is_some_and(|l| l.is_punct(self.source, punct))
+    }
+
+    fn is_ident(&self, i: usize) -> bool {
+        self.lexemes
+            .get(i)
+            .is_some_and(|l| l.kind() == LexemeKind::Ident)
+    }
+
+    /// The index of the delimiter closing the one opened at `open`
+    fn closing(&self, open: usize, end: usize) -> usize {
+        let mut depth = 0;
+        for i in open..end {
+            match self.text(i) {
+                "(" | "[" | "{" if self.lexemes[i].kind() == LexemeKind::Punct => depth += 1,
+                ")" | "]" | "}" if self.lexemes[i].kind() == LexemeKind::Punct => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        end.saturating_sub(1)
+    }
+
+    /// The index of the `{` opening the body of the item whose header starts at `start`,
+    /// or `None` if the item ends with a `;` first
+    fn body(&self, start: usize, end: usize) -> Option<usize> {
+        let mut i = start;
+        while i < end {
+            match self.text(i) {
+                "{" if self.is_punct(i, "{") => return Some(i),
+                ";" if self.is_punct(i, ";") => return None,
+                "(" | "[" if self.lexemes[i].kind() == LexemeKind::Punct => {
+                    i = self.closing(i, end);
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn span(&self, first: usize, last: usize) -> Range<usize> {
+        self.lexemes[first].span().start..self.lexemes[last].span().end
+    }
+
+    /// Parses the items between lexemes `start` and `end`
+    fn items(&self, start: usize, end: usize) -> Vec<Item> {
+        let mut items = vec![];
+        // The first attribute or modifier of the item being parsed
+        let mut item_start: Option<usize> = None;
+        let mut test = false;
+        let mut unconstrained = false;
+        let mut i = start;
+
+        while i < end {
+            let lexeme = &self.lexemes[i];
+            let text = self.text(i);
+
+            if lexeme.kind() == LexemeKind::Attribute {
+                item_start.get_or_insert(i);
+                test |= text == "#[test]" || text.starts_with("#[test(");
+                i += 1;
+                continue;
+            }
+
+            let first = item_start.unwrap_or(i);
+
+            match text {
+                "pub" | "unconstrained" if lexeme.kind() == LexemeKind::Ident => {
+                    item_start.get_or_insert(i);
+                    unconstrained |= text == "unconstrained";
+                    // i.e: `pub(crate)`
+                    if self.is_punct(i + 1, "(") {
+                        i = self.closing(i + 1, end);
+                    }
+                    i += 1;
+                    continue;
+                }
+                "comptime" if !self.is_punct(i + 1, "{") => {
+                    item_start.get_or_insert(i);
+                    i += 1;
+                    continue;
+                }
+                "comptime" => {
+                    let close = self.closing(i + 1, end);
+                    items.push(Item {
+                        kind: ItemKind::Comptime,
+                        name: "comptime".to_string(),
+                        span: self.span(first, close),
+                        test: false,
+                        unconstrained: false,
+                        params: vec![],
+                        return_type: None,
+                        tail: None,
+                        children: self.nested(i + 2, close),
+                    });
+                    i = close + 1;
+                }
+                "mod" if self.is_ident(i + 1) && self.is_punct(i + 2, "{") => {
+                    let close = self.closing(i + 2, end);
+                    items.push(Item {
+                        kind: ItemKind::Module,
+                        name: self.text(i + 1).to_string(),
+                        span: self.span(first, close),
+                        test: false,
+                        unconstrained: false,
+                        params: vec![],
+                        return_type: None,
+                        tail: None,
+                        children: self.items(i + 3, close),
+                    });
+                    i = close + 1;
+                }
+                "fn" if self.is_ident(i + 1) => match self.body(i + 2, end) {
+                    Some(open) => {
+                        let close = self.closing(open, end);
+                        items.push(Item {
+                            kind: ItemKind::Function,
+                            name: self.text(i + 1).to_string(),
+                            span: self.span(first, close),
+                            test,
+                            unconstrained,
+                            params: self.params(i + 2, open),
+                            return_type: self.return_type(i + 2, open),
+                            tail: self.tail(open, close),
+                            children: self.nested(open + 1, close),
+                        });
+                        i = close + 1;
+                    }
+                    None => i += 2,
+                },
+                "impl" | "trait" => match self.body(i + 1, end) {
+                    Some(open) => {
+                        let close = self.closing(open, end);
+                        let (kind, name) = if text == "impl" {
+                            (ItemKind::Impl, self.impl_type(i + 1, open))
+                        } else {
+                            (ItemKind::Trait, self.text(i + 1).to_string())
+                        };
+                        items.push(Item {
+                            kind,
+                            name,
+                            span: self.span(first, close),
+                            test: false,
+                            unconstrained: false,
+                            params: vec![],
+                            return_type: None,
+                            tail: None,
+                            children: self.items(open + 1, close),
+                        });
+                        i = close + 1;
+                    }
+                    None => i += 1,
+                },
+                "{" | "(" | "[" if lexeme.kind() == LexemeKind::Punct => {
+                    // i.e: a struct body or a global's initializer
+                    i = self.closing(i, end) + 1;
+                }
+                _ => {
+                    i += 1;
+                    if text != ";" {
+                        continue;
+                    }
+                }
+            }
+
+            item_start = None;
+            test = false;
+            unconstrained = false;
+        }
+
+        items
+    }
+
+    /// Finds the `comptime` blocks nested in a function body
+    fn nested(&self, start: usize, end: usize) -> Vec<Item> {
+        let mut items = vec![];
+        let mut i = start;
+        while i < end {
+            if self.text(i) == "comptime" && self.is_punct(i + 1, "{") {
+                let close = self.closing(i + 1, end);
+                items.push(Item {
+                    kind: ItemKind::Comptime,
+                    name: "comptime".to_string(),
+                    span: self.span(i, close),
+                    test: false,
+                    unconstrained: false,
+                    params: vec![],
+                    return_type: None,
+                    tail: None,
+                    children: self.nested(i + 2, close),
+                });
+                i = close;
+            }
+            i += 1;
+        }
+        items
+    }
+
+    /// The types of the parameters in the function header between `start` and `end`,
+    /// i.e: `Field` and `[u8; 32]` in `(self, a: Field, b: [u8; 32])`
+    fn params(&self, start: usize, end: usize) -> Vec<String> {
+        let Some(open) = (start..end).find(|&i| self.is_punct(i, "(")) else {
+            return vec![];
+        };
+        let close = self.closing(open, end);
+
+        let mut params = vec![];
+        let mut colon = None;
+        let mut depth = 0;
+        for i in open + 1..=close {
+            let lexeme = &self.lexemes[i];
+            match (lexeme.kind(), self.text(i)) {
+                (LexemeKind::Punct, "(" | "[" | "{") | (LexemeKind::GenericBracket, "<") => {
+                    depth += 1
+                }
+                (LexemeKind::Punct, ")" | "]" | "}") | (LexemeKind::GenericBracket, ">")
+                    if depth > 0 =>
+                {
+                    depth -= 1
+                }
+                (LexemeKind::Punct, ":") if depth == 0 => colon = colon.or(Some(i)),
+                (LexemeKind::Punct, "," | ")") if depth == 0 => {
+                    // `self` has no type annotation
+                    if let Some(colon) = colon.take().filter(|&colon| colon + 1 < i) {
+                        params.push(self.source[self.span(colon + 1, i - 1)].to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        params
+    }
+
+    /// The return type in the function header between `start` and `end`, without its
+    /// visibility, i.e: `[u8; 32]` in `-> pub [u8; 32]`. `None` for `()`.
+    fn return_type(&self, start: usize, end: usize) -> Option<String> {
+        // Skipping the parameters, which may have function types themselves
+        let mut arrow = start;
+        while !self.is_punct(arrow, "->") {
+            if self.is_punct(arrow, "(") {
+                arrow = self.closing(arrow, end);
+            }
+            arrow += 1;
+            if arrow >= end {
+                return None;
+            }
+        }
+        let mut first = arrow + 1;
+        while matches!(self.text(first), "pub" | "return_data" | "call_data") {
+            first += 1;
+            // i.e: `call_data(0)`
+            if self.is_punct(first, "(") {
+                first = self.closing(first, end) + 1;
+            }
+        }
+        let last = (first..end)
+            .find(|&i| self.text(i) == "where")
+            .unwrap_or(end)
+            .checked_sub(1)?;
+        if last < first {
+            return None;
+        }
+
+        let return_type = &self.source[self.span(first, last)];
+        (return_type != "()").then(|| return_type.to_string())
+    }
+
+    /// The span of the tail expression of the block between `open` and `close`
+    fn tail(&self, open: usize, close: usize) -> Option<Range<usize>> {
+        let mut start = open + 1;
+        let mut i = open + 1;
+        while i < close {
+            match self.text(i) {
+                ";" if self.is_punct(i, ";") => start = i + 1,
+                "{" | "(" | "[" if self.lexemes[i].kind() == LexemeKind::Punct => {
+                    let end = self.closing(i, close);
+                    // A block statement, i.e: `for .. { .. }`, needs no `;`, but an `if` or
+                    // `match` at the very end of the body is the tail expression itself
+                    let block_statement = self.text(i) == "{"
+                        && (i == start || BLOCK_KEYWORDS.contains(&self.text(start)))
+                        && end + 1 < close
+                        && self.text(end + 1) != "else";
+                    if block_statement {
+                        start = end + 1;
+                    }
+                    i = end;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        (start < close).then(|| self.span(start, close - 1))
+    }
+
+    /// The name of the type an impl block is for, i.e: `Point` in `impl<T> Eq for Point<T>`
+    fn impl_type(&self, start: usize, end: usize) -> String {
+        let header = (start..end)
+            .position(|i| self.text(i) == "for")
+            .map_or(start, |n| start + n + 1);
+
+        let mut name = String::new();
+        let mut depth = 0;
+        for i in header..end {
+            match self.lexemes[i].kind() {
+                LexemeKind::GenericBracket if self.text(i) == "<" => depth += 1,
+                LexemeKind::GenericBracket => depth -= 1,
+                LexemeKind::Ident if depth == 0 && self.text(i) == "where" => break,
+                LexemeKind::Ident if depth == 0 => name = self.text(i).to_string(),
+                _ => {}
+            }
+        }
+        name
+    }
+}
+
+/// Keywords that start a statement ending with a block, i.e: `for i in 0..3 { .. }`
+const BLOCK_KEYWORDS: [&str; 7] = ["for", "if", "while", "loop", "match", "unsafe", "comptime"];
+
+/// Keywords that start or separate statements, so a predicate never extends past them
+const STATEMENT_KEYWORDS: [&str; 12] = [
+    "assert",
+    "assert_eq",
+    "constrain",
+    "else",
+    "for",
+    "if",
+    "in",
+    "let",
+    "match",
+    "return",
+    "while",
+    "comptime",
+];
+
+/// The span of the condition of the `if` or `assert` at `keyword`,
+/// i.e: `x == 0` in `if x == 0 { .. }` or in `assert(x == 0, "msg")`.
+pub fn condition_span(source: &str, lexemes: &[Lexeme], keyword: usize) -> Option<Range<usize>> {
+    let (start, ends): (usize, &[&str]) = match lexemes[keyword].text(source) {
+        "if" => (keyword + 1, &["{"]),
+        "assert" if lexemes.get(keyword + 1)?.is_punct(source, "(") => (keyword + 2, &[",", ")"]),
+        _ => return None,
+    };
+
+    let mut depth = 0;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(start) {
+        let text = lexeme.text(source);
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        if depth == 0 && ends.contains(&text) {
+            if i == start {
+                return None;
+            }
+            return Some(lexemes[start].span().start..lexemes[i - 1].span().end);
+        }
+        match text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return None,
+            ")" | "]" | "}" => depth -= 1,
+            ";" => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The spans of the start bound, the `..` or `..=` and the end bound of the range the `for`
+/// loop at `keyword` iterates over, i.e: `0`, `..` and `n.len()` in `for i in 0..n.len() { .. }`
+pub fn loop_range(
+    source: &str,
+    lexemes: &[Lexeme],
+    keyword: usize,
+) -> Option<(Range<usize>, Range<usize>, Range<usize>)> {
+    if lexemes[keyword].text(source) != "for" || lexemes.get(keyword + 2)?.text(source) != "in" {
+        return None;
+    }
+    let start = keyword + 3;
+
+    let mut depth = 0;
+    let mut operator = None;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(start) {
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        let text = lexeme.text(source);
+        match text {
+            ".." | "..=" if depth == 0 && operator.is_none() => operator = Some(i),
+            "{" if depth == 0 => {
+                let operator = operator.filter(|&op| op > start && op + 1 < i)?;
+                return Some((
+                    lexemes[start].span().start..lexemes[operator - 1].span().end,
+                    lexemes[operator].span(),
+                    lexemes[operator + 1].span().start..lexemes[i - 1].span().end,
+                ));
+            }
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return None,
+            ")" | "]" | "}" => depth -= 1,
+            ";" => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The span of the call to the function named at `name`, including its path and arguments,
+/// i.e: `utils::sqrt_hint(x)`. Method calls and function definitions are not included.
+pub fn call_span(source: &str, lexemes: &[Lexeme], name: usize) -> Option<Range<usize>> {
+    if lexemes[name].kind() != LexemeKind::Ident || !lexemes.get(name + 1)?.is_punct(source, "(") {
+        return None;
+    }
+
+    let mut start = name;
+    while start >= 2
+        && lexemes[start - 1].is_punct(source, "::")
+        && lexemes[start - 2].kind() == LexemeKind::Ident
+    {
+        start -= 2;
+    }
+    if let Some(before) = start.checked_sub(1).map(|i| &lexemes[i]) {
+        if before.is_punct(source, ".") || before.text(source) == "fn" {
+            return None;
+        }
+    }
+
+    let mut depth = 0;
+    for lexeme in lexemes.iter().skip(name + 1) {
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        match lexeme.text(source) {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(lexemes[start].span().start..lexeme.span().end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The spans of the arguments of the call to the function named at `name`,
+/// i.e: `x` and `f(y, z)` in `add(x, f(y, z))`
+pub fn call_arguments(source: &str, lexemes: &[Lexeme], name: usize) -> Option<Vec<Range<usize>>> {
+    call_span(source, lexemes, name)?;
+
+    let mut arguments = vec![];
+    let mut first = name + 2;
+    let mut depth = 0;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(name + 2) {
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        match lexeme.text(source) {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "," if depth == 0 => {
+                // i.e: a trailing comma, or no arguments at all
+                if i > first {
+                    arguments.push(lexemes[first].span().start..lexemes[i - 1].span().end);
+                }
+                if lexeme.text(source) == ")" {
+                    return Some(arguments);
+                }
+                first = i + 1;
+            }
+            ")" | "]" | "}" => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The index of the first lexeme of the operand ending at `last`, i.e: `a.b[0]` or `f(x)`.
+/// `None` for anything more complex than a path, literal, call, field access or index.
+fn operand_start(source: &str, lexemes: &[Lexeme], last: usize) -> Option<usize> {
+    let mut i = last;
+    loop {
+        let lexeme = &lexemes[i];
+        i = match (lexeme.kind(), lexeme.text(source)) {
+            (LexemeKind::Punct, ")" | "]") => {
+                let mut depth = 0;
+                (0..=i).rev().find(|&j| {
+                    match (lexemes[j].kind(), lexemes[j].text(source)) {
+                        (LexemeKind::Punct, ")" | "]" | "}") => depth += 1,
+                        (LexemeKind::Punct, "(" | "[" | "{") => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })?
+            }
+            (LexemeKind::Ident, text) if !is_keyword(text) || text == "self" => i,
+            (LexemeKind::Int | LexemeKind::Str, _) => i,
+            _ => return None,
+        };
+
+        let Some(before) = i.checked_sub(1) else {
+            return Some(i);
+        };
+        let previous = &lexemes[before];
+        let named = previous.kind() == LexemeKind::Ident && !is_keyword(previous.text(source));
+        if previous.is_punct(source, ".") || previous.is_punct(source, "::") {
+            i = before.checked_sub(1)?;
+        } else if lexemes[i].is_punct(source, "(") && named
+            || lexemes[i].is_punct(source, "[")
+                && (named || previous.is_punct(source, ")") || previous.is_punct(source, "]"))
+        {
+            // A call or an index, i.e: `f(x)` or `arr[0]`
+            i = before;
+        } else {
+            return Some(i);
+        }
+    }
+}
+
+/// The index of the last lexeme of the operand starting at `first`, see `operand_start()`
+fn operand_end(source: &str, lexemes: &[Lexeme], first: usize) -> Option<usize> {
+    let mut i = first;
+    loop {
+        let lexeme = lexemes.get(i)?;
+        i = match (lexeme.kind(), lexeme.text(source)) {
+            (LexemeKind::Punct, "(" | "[") => {
+                let mut depth = 0;
+                (i..lexemes.len()).find(|&j| {
+                    match (lexemes[j].kind(), lexemes[j].text(source)) {
+                        (LexemeKind::Punct, "(" | "[" | "{") => depth += 1,
+                        (LexemeKind::Punct, ")" | "]" | "}") => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })?
+            }
+            (LexemeKind::Ident, text) if !is_keyword(text) || text == "self" => i,
+            (LexemeKind::Int | LexemeKind::Str, _) => i,
+            _ => return None,
+        };
+
+        let Some(after) = lexemes.get(i + 1) else {
+            return Some(i);
+        };
+        if after.is_punct(source, ".") || after.is_punct(source, "::") {
+            i += 2;
+        } else if after.is_punct(source, "(") || after.is_punct(source, "[") {
+            // A call or an index, i.e: `f(x)` or `arr[0]`
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+}
+
+/// The span of the index expression of the `[` at `open`, i.e: `i + 1` in `arr[i + 1]`.
+/// `None` for array literals and types, i.e: `[0; 4]`.
+pub fn index_span(source: &str, lexemes: &[Lexeme], open: usize) -> Option<Range<usize>> {
+    let bracket = &lexemes[open];
+    if !bracket.is_punct(source, "[") || bracket.in_type() {
+        return None;
+    }
+    let before = &lexemes[open.checked_sub(1)?];
+    let indexable = match before.kind() {
+        LexemeKind::Ident => !is_keyword(before.text(source)),
+        LexemeKind::Punct => matches!(before.text(source), ")" | "]"),
+        _ => false,
+    };
+    if !indexable {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(open) {
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        match lexeme.text(source) {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return (i > open + 1)
+                        .then(|| lexemes[open + 1].span().start..lexemes[i - 1].span().end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the identifier at `index` names a struct field being read or written, i.e: `x` in
+/// `p.x`, rather than a method being called
+pub fn is_field_access(source: &str, lexemes: &[Lexeme], index: usize) -> bool {
+    lexemes[index].kind() == LexemeKind::Ident
+        && index
+            .checked_sub(1)
+            .is_some_and(|before| lexemes[before].is_punct(source, "."))
+        && !lexemes
+            .get(index + 1)
+            .is_some_and(|after| after.is_punct(source, "(") || after.is_punct(source, "::"))
+}
+
+/// The types the file implements `Default` for, by name,
+/// i.e: `Point` for `impl<T> Default for geometry::Point<T> { ... }`
+pub fn default_impls(source: &str, lexemes: &[Lexeme]) -> Vec<String> {
+    let mut types = vec![];
+    for (keyword, lexeme) in lexemes.iter().enumerate() {
+        if lexeme.kind() != LexemeKind::Ident
+            || lexeme.text(source) != "for"
+            || !keyword
+                .checked_sub(1)
+                .is_some_and(|before| lexemes[before].text(source) == "Default")
+        {
+            continue;
+        }
+        // The last segment of the type's path, without its generics
+        let mut name = None;
+        for lexeme in &lexemes[keyword + 1..] {
+            match (lexeme.kind(), lexeme.text(source)) {
+                (LexemeKind::Ident, text) => name = Some(text),
+                (LexemeKind::Punct, "::") => {}
+                _ => break,
+            }
+        }
+        types.extend(name.map(str::to_string));
+    }
+    types
+}
+
+/// The fields of every struct in the file, as names and types,
+/// i.e: `[("x", "Field"), ("y", "Field")]` for `struct Point { x: Field, pub y: Field }`
+pub fn struct_fields(source: &str, lexemes: &[Lexeme]) -> Vec<Vec<(String, String)>> {
+    let mut structs = vec![];
+    for (keyword, lexeme) in lexemes.iter().enumerate() {
+        if lexeme.kind() != LexemeKind::Ident || lexeme.text(source) != "struct" {
+            continue;
+        }
+        // Skipping the name and any generic parameters
+        let Some(open) = lexemes
+            .iter()
+            .enumerate()
+            .skip(keyword + 2)
+            .take_while(|(_, l)| !l.is_punct(source, ";"))
+            .find(|(_, l)| l.is_punct(source, "{"))
+            .map(|(i, _)| i)
+        else {
+            continue;
+        };
+
+        let mut fields = vec![];
+        let mut first = open + 1;
+        let mut depth = 0;
+        for (i, lexeme) in lexemes.iter().enumerate().skip(open + 1) {
+            let text = lexeme.text(source);
+            let separator = lexeme.kind() == LexemeKind::Punct && matches!(text, "," | "}");
+            if separator && depth == 0 {
+                let colon = (first..i).find(|&c| lexemes[c].is_punct(source, ":"));
+                if let Some(colon) = colon.filter(|&colon| colon > first && colon + 1 < i) {
+                    fields.push((
+                        lexemes[colon - 1].text(source).to_string(),
+                        source[lexemes[colon + 1].span().start..lexemes[i - 1].span().end]
+                            .to_string(),
+                    ));
+                }
+                if text == "}" {
+                    break;
+                }
+                first = i + 1;
+                continue;
+            }
+            match (lexeme.kind(), text) {
+                (LexemeKind::Punct, "(" | "[" | "{") | (LexemeKind::GenericBracket, "<") => {
+                    depth += 1
+                }
+                (LexemeKind::Punct, ")" | "]" | "}") | (LexemeKind::GenericBracket, ">") => {
+                    depth -= 1
+                }
+                _ => {}
+            }
+        }
+        structs.push(fields);
+    }
+    structs
+}
+
+/// How tightly an arithmetic or shift operator binds, higher binding more tightly
+fn precedence(operator: &str) -> Option<u8> {
+    match operator {
+        "*" | "/" | "%" => Some(3),
+        "+" | "-" => Some(2),
+        "<<" | ">>" => Some(1),
+        _ => None,
+    }
+}
+
+/// The spans of the operands of the binary operator at `operator`, i.e: `a.x` and `f(b)` in
+/// `a.x - f(b)`. `None` unless both are simple operands, see `operand_start()`, and neither is
+/// also an operand of a neighbouring operator that takes it first, i.e: `*` in `a - b * c`.
+pub fn binary_operands(
+    source: &str,
+    lexemes: &[Lexeme],
+    operator: usize,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let own = precedence(lexemes[operator].text(source))?;
+    // Operators are left-associative, so an equally tight neighbour only takes the left operand
+    let bounds = |lexeme: &Lexeme, closing: &[&str], takes: &dyn Fn(u8) -> bool| match lexeme.kind()
+    {
+        LexemeKind::Punct => {
+            let text = lexeme.text(source);
+            match precedence(text).filter(|_| lexeme.binary()) {
+                Some(neighbour) => !takes(neighbour),
+                None => {
+                    closing.contains(&text)
+                        || matches!(
+                            text,
+                            "," | ";"
+                                | ":"
+                                | "=>"
+                                | "<"
+                                | ">"
+                                | "&"
+                                | "|"
+                                | "^"
+                                | "&&"
+                                | "||"
+                                | ".."
+                                | "..="
+                        )
+                        || text.ends_with('=')
+                }
+            }
+        }
+        LexemeKind::Ident => is_keyword(lexeme.text(source)) && lexeme.text(source) != "as",
+        _ => false,
+    };
+
+    let start = operand_start(source, lexemes, operator.checked_sub(1)?)?;
+    let end = operand_end(source, lexemes, operator + 1)?;
+    if let Some(before) = start.checked_sub(1).map(|i| &lexemes[i]) {
+        if !bounds(before, &["(", "[", "{"], &|neighbour| neighbour >= own) {
+            return None;
+        }
+    }
+    if let Some(after) = lexemes.get(end + 1) {
+        if !bounds(after, &[")", "]", "}", "{"], &|neighbour| neighbour > own) {
+            return None;
+        }
+    }
+
+    Some((
+        lexemes[start].span().start..lexemes[operator - 1].span().end,
+        lexemes[operator + 1].span().start..lexemes[end].span().end,
+    ))
+}
+
+/// Whether a statement can start at `index`, i.e: after a `;` or at the start of a block
+fn starts_statement(source: &str, lexemes: &[Lexeme], index: usize) -> bool {
+    match index.checked_sub(1).map(|i| &lexemes[i]) {
+        None => true,
+        Some(before) => [";", "{", "}"].iter().any(|p| before.is_punct(source, p)),
+    }
+}
+
+/// The span of the assignment or call statement starting at `start`, including its `;`,
+/// i.e: `c = 6;`, `c += 1;` or `v.push(x);`. `let` bindings and constraints are never included,
+/// since deleting a binding that later code uses can't compile.
+pub fn statement_span(source: &str, lexemes: &[Lexeme], start: usize) -> Option<Range<usize>> {
+    let first = &lexemes[start];
+    // Block statements have no trailing `;`, so the span would run into the next statement
+    if first.kind() != LexemeKind::Ident
+        || is_keyword(first.text(source))
+        || BLOCK_KEYWORDS.contains(&first.text(source))
+        || !starts_statement(source, lexemes, start)
+    {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut assignment = false;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(start + 1) {
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        let text = lexeme.text(source);
+        match text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return None,
+            ")" | "]" | "}" => depth -= 1,
+            ";" if depth == 0 => {
+                let call = lexemes[i - 1].is_punct(source, ")");
+                return (assignment || call).then(|| first.span().start..lexeme.span().end);
+            }
+            "=" | "+=" | "-=" | "*=" | "/=" | "%=" | "&=" | "|=" | "^=" | "<<=" | ">>="
+                if depth == 0 =>
+            {
+                assignment = true
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The span of the `assert`, `assert_eq` or `constrain` statement at `keyword`,
+/// including its trailing `;`, i.e: `assert(x != y);`
+pub fn constraint_span(source: &str, lexemes: &[Lexeme], keyword: usize) -> Option<Range<usize>> {
+    let text = lexemes[keyword].text(source);
+    if !matches!(text, "assert" | "assert_eq" | "constrain") {
+        return None;
+    }
+
+    // Only whole statements, not i.e: `let ok = assert(..)` or `foo.assert(..)`
+    if !starts_statement(source, lexemes, keyword) {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut end = None;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(keyword + 1) {
+        let text = lexeme.text(source);
+        if lexeme.kind() != LexemeKind::Punct {
+            continue;
+        }
+        match text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return None,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                // `assert(..)` ends with its arguments, even without a `;`
+                if depth == 0 && text == ")" && lexemes[keyword].text(source) != "constrain" {
+                    end = Some(i);
+                    break;
+                }
+            }
+            ";" if depth == 0 => {
+                end = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut end = end?;
+    if lexemes
+        .get(end + 1)
+        .is_some_and(|l| l.is_punct(source, ";"))
+    {
+        end += 1;
+    }
+    Some(lexemes[keyword].span().start..lexemes[end].span().end)
+}
+
+/// Whether the lexeme at `index` is the integer or `Field` type of a cast, i.e: `u64` in `c as u64`
+pub fn is_cast_type(source: &str, lexemes: &[Lexeme], index: usize) -> bool {
+    let text = lexemes[index].text(source);
+    index
+        .checked_sub(1)
+        .is_some_and(|before| lexemes[before].text(source) == "as")
+        && (text == "Field" || is_int_type(text))
+        // Not a path, i.e: `x as u64::Foo`
+        && !lexemes.get(index + 1).is_some_and(|l| l.is_punct(source, "::"))
+}
+
+/// Whether the cast whose type is at `index` is an operand of a binary operator, i.e: `c as u64`
+/// in `c as u64 > x as u64`. Changing the type of only one side there never builds, so these
+/// casts are narrowed inside instead, see `Token::CastOperand`.
+pub fn is_cast_operand(source: &str, lexemes: &[Lexeme], index: usize) -> bool {
+    // Compound assignments are binary lexemes too, but their operands are independent
+    let is_operator = |lexeme: &Lexeme| {
+        let text = lexeme.text(source);
+        lexeme.binary() && (!text.ends_with('=') || matches!(text, "==" | "!=" | "<=" | ">="))
+    };
+    let Some(mut start) = index
+        .checked_sub(2)
+        .and_then(|last| operand_start(source, lexemes, last))
+    else {
+        return false;
+    };
+    let mut end = index;
+    // Look through parentheses around the whole cast, i.e: `(c as u64) > x`
+    while start > 0
+        && lexemes[start - 1].is_punct(source, "(")
+        && lexemes
+            .get(end + 1)
+            .is_some_and(|l| l.is_punct(source, ")"))
+        && !(start > 1 && lexemes[start - 2].kind() == LexemeKind::Ident)
+    {
+        start -= 1;
+        end += 1;
+    }
+    lexemes.get(end + 1).is_some_and(is_operator)
+        || start
+            .checked_sub(1)
+            .is_some_and(|before| is_operator(&lexemes[before]))
+}
+
+/// Whether the integer literal at `literal` can be changed without breaking the build.
+/// Tuple indexes (`t.0`) and array lengths (`[0; 32]`) can't.
+pub fn literal_is_mutable(source: &str, lexemes: &[Lexeme], literal: usize) -> bool {
+    let is_punct = |i: Option<usize>, p: &str| {
+        i.and_then(|i| lexemes.get(i))
+            .is_some_and(|lexeme: &Lexeme| lexeme.is_punct(source, p))
+    };
+    let before = literal.checked_sub(1);
+    let after = Some(literal + 1);
+
+    !(is_punct(before, ".") || is_punct(before, ";") && is_punct(after, "]"))
+}
+
+/// The span of the whole comparison the relational operator at `operator` is part of,
+/// i.e: `a + 1 < b` in `assert(a + 1 < b & c)`.
+/// In Noir, `&`, `|` and `^` bind more loosely than comparisons.
+pub fn predicate_span(source: &str, lexemes: &[Lexeme], operator: usize) -> Option<Range<usize>> {
+    let is_boundary = |lexeme: &Lexeme| match lexeme.kind() {
+        LexemeKind::Punct => {
+            let text = lexeme.text(source);
+            matches!(
+                text,
+                "," | ";" | "=" | "=>" | ":" | "{" | "}" | "->" | ".." | "..="
+            ) || text.ends_with('=') && !matches!(text, "==" | "!=" | "<=" | ">=")
+                || lexeme.binary()
+                    && matches!(
+                        text,
+                        "&" | "|" | "^" | "&&" | "||" | "==" | "!=" | "<" | "<=" | ">" | ">="
+                    )
+        }
+        LexemeKind::Ident => STATEMENT_KEYWORDS.contains(&lexeme.text(source)),
+        LexemeKind::Attribute => true,
+        _ => false,
+    };
+
+    // Walk left, skipping over bracketed groups
+    let mut start = operator;
+    let mut depth = 0;
+    for i in (0..operator).rev() {
+        let lexeme = &lexemes[i];
+        let text = lexeme.text(source);
+        if lexeme.kind() == LexemeKind::Punct && matches!(text, ")" | "]") {
+            depth += 1;
+        } else if lexeme.kind() == LexemeKind::Punct && matches!(text, "(" | "[") {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        } else if depth == 0 && is_boundary(lexeme) {
+            break;
+        }
+        start = i;
+    }
+
+    // Walk right, skipping over bracketed groups
+    let mut end = operator;
+    let mut depth = 0;
+    for (i, lexeme) in lexemes.iter().enumerate().skip(operator + 1) {
+        let text = lexeme.text(source);
+        if lexeme.kind() == LexemeKind::Punct && matches!(text, "(" | "[") {
+            depth += 1;
+        } else if lexeme.kind() == LexemeKind::Punct && matches!(text, ")" | "]") {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        } else if depth == 0 && is_boundary(lexeme) {
+            break;
+        }
+        end = i;
+    }
+
+    if start == operator || end == operator {
+        return None;
+    }
+    Some(lexemes[start].span().start..lexemes[end].span().end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+
+    fn tree(source: &str) -> SyntaxTree {
+        parse(source, &lex(source))
+    }
+
+    #[test]
+    fn test_parse_items() {
+        let source = r#"
+            use dep::std;
+            global N: u32 = 2 + 1;
+            struct Point { x: Field, y: Field }
+            mod math {
+                pub fn add(a: Field, b: Field) -> Field { a + b }
+                impl<T> Eq for Point<T> where T: Eq {
+                    fn eq(self, other: Self) -> bool { self.x == other.x }
+                }
+            }
+            trait Zero { fn zero() -> Self; fn is_zero(self) -> bool { self == Self::zero() } }
+            comptime { let x = 1 * 2; }
+            #[test(should_fail)]
+            unconstrained fn test_add() { assert(math::add(1, 2) != 4); }
+        "#;
+        let tree = tree(source);
+        let kinds: Vec<(ItemKind, &str)> =
+            tree.items().iter().map(|i| (i.kind(), i.name())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ItemKind::Module, "math"),
+                (ItemKind::Trait, "Zero"),
+                (ItemKind::Comptime, "comptime"),
+                (ItemKind::Function, "test_add"),
+            ]
+        );
+
+        let math: Vec<(ItemKind, &str)> = tree.items()[0]
+            .children()
+            .iter()
+            .map(|i| (i.kind(), i.name()))
+            .collect();
+        assert_eq!(
+            math,
+            vec![(ItemKind::Function, "add"), (ItemKind::Impl, "Point")]
+        );
+        assert_eq!(tree.items()[1].children().len(), 1);
+        assert!(tree.items()[3].is_test());
+        assert!(tree.items()[3].is_unconstrained());
+        assert!(!tree.items()[1].children()[0].is_unconstrained());
+        assert!(tree.items()[3].span().start < source.find("unconstrained").unwrap() + 1);
+    }
+
+    #[test]
+    fn test_item_path() {
+        let source = "mod math { impl Point { fn add(self) -> Field { self.x + self.y } } }\nglobal N: u32 = 1 + 2;";
+        let tree = tree(source);
+        let module = vec!["my_crate".to_string()];
+
+        let plus = source.find('+').unwrap();
+        assert_eq!(tree.item_path(&module, plus), "my_crate::math::Point::add");
+        assert_eq!(tree.enclosing_function(plus).unwrap().name(), "add");
+
+        let global = source.rfind('+').unwrap();
+        assert_eq!(tree.item_path(&module, global), "my_crate");
+        assert!(tree.enclosing_function(global).is_none());
+    }
+
+    #[test]
+    fn test_test_functions() {
+        let source = "fn a() { if x { y } }\n#[test]\nfn t() { if x { y } else { z } }\nfn b() {}";
+        let tree = tree(source);
+        let tests = tree.test_functions();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(
+            &source[tests[0].span()],
+            "#[test]\nfn t() { if x { y } else { z } }"
+        );
+        assert!(tree.in_test(source.find('z').unwrap()));
+        assert!(!tree.in_test(source.find('y').unwrap()));
+    }
+
+    #[test]
+    fn test_predicate_span() {
+        fn predicate<'a>(source: &'a str, operator: &str) -> Option<&'a str> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.binary() && l.text(source) == operator)
+                .unwrap();
+            predicate_span(source, &lexemes, index).map(|span| &source[span])
+        }
+
+        assert_eq!(
+            predicate("assert(c as u64 > x as u64);", ">"),
+            Some("c as u64 > x as u64")
+        );
+        assert_eq!(
+            predicate("if foo(a, b)[0] + 1 == x.y { }", "=="),
+            Some("foo(a, b)[0] + 1 == x.y")
+        );
+        assert_eq!(
+            predicate("let ok = (a < b) & (c >= -d);", ">="),
+            Some("c >= -d")
+        );
+        assert_eq!(predicate("let ok = a < b | c;", "<"), Some("a < b"));
+    }
+
+    #[test]
+    fn test_condition_span() {
+        fn condition<'a>(source: &'a str, keyword: &str) -> Option<&'a str> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.text(source) == keyword)
+                .unwrap();
+            condition_span(source, &lexemes, index).map(|span| &source[span])
+        }
+
+        assert_eq!(condition("if x == 0 { c = 6; }", "if"), Some("x == 0"));
+        assert_eq!(
+            condition("if foo(a, { b }) & c[0] { }", "if"),
+            Some("foo(a, { b }) & c[0]")
+        );
+        assert_eq!(
+            condition("assert(c as u64 > x, \"too small\");", "assert"),
+            Some("c as u64 > x")
+        );
+        assert_eq!(condition("assert(f(a, b));", "assert"), Some("f(a, b)"));
+        assert_eq!(condition("assert_eq(a, b);", "assert_eq"), None);
+        assert_eq!(condition("let assert = 1;", "assert"), None);
+    }
+
+    #[test]
+    fn test_loop_range() {
+        fn range(source: &str) -> Option<(&str, &str, &str)> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.text(source) == "for")
+                .unwrap();
+            loop_range(source, &lexemes, index)
+                .map(|(start, op, end)| (&source[start], &source[op], &source[end]))
+        }
+
+        assert_eq!(range("for i in 0..10 { s += i; }"), Some(("0", "..", "10")));
+        assert_eq!(
+            range("for i in a + 1..=f(b, { c }) { }"),
+            Some(("a + 1", "..=", "f(b, { c })"))
+        );
+        assert_eq!(
+            range("for i in 0..arr.len() { }"),
+            Some(("0", "..", "arr.len()"))
+        );
+        assert_eq!(range("for x in arr { }"), None);
+        assert_eq!(range("for i in ..n { }"), None);
+    }
+
+    #[test]
+    fn test_call_span() {
+        fn call<'a>(source: &'a str, name: &str) -> Option<&'a str> {
+            let lexemes = lex(source);
+            let index = lexemes.iter().position(|l| l.text(source) == name).unwrap();
+            call_span(source, &lexemes, index).map(|span| &source[span])
+        }
+
+        assert_eq!(
+            call("let r = sqrt_hint(x, f(y)) * 2;", "sqrt_hint"),
+            Some("sqrt_hint(x, f(y))")
+        );
+        assert_eq!(
+            call("let r = unsafe { utils::sqrt_hint(x) };", "sqrt_hint"),
+            Some("utils::sqrt_hint(x)")
+        );
+        assert_eq!(call("let r = self.sqrt_hint(x);", "sqrt_hint"), None);
+        assert_eq!(call("fn sqrt_hint(x: Field) {}", "sqrt_hint"), None);
+        assert_eq!(call("let sqrt_hint = 1;", "sqrt_hint"), None);
+    }
+
+    #[test]
+    fn test_params() {
+        let source =
+            "impl Foo { fn f(self, a: Field, b: [u8; 32], c: Vec<(u8, bool)>) -> u8 { a } }";
+        let tree = tree(source);
+        assert_eq!(
+            tree.functions()[0].params(),
+            ["Field", "[u8; 32]", "Vec<(u8, bool)>"]
+        );
+        assert!(tree.items()[0].params().is_empty());
+    }
+
+    #[test]
+    fn test_call_arguments() {
+        fn arguments(source: &str) -> Option<Vec<&str>> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.text(source) == "add")
+                .unwrap();
+            call_arguments(source, &lexemes, index)
+                .map(|spans| spans.into_iter().map(|span| &source[span]).collect())
+        }
+
+        assert_eq!(
+            arguments("let c = add(x, f(y, z), [1, 2],);"),
+            Some(vec!["x", "f(y, z)", "[1, 2]"])
+        );
+        assert_eq!(arguments("add();"), Some(vec![]));
+        assert_eq!(arguments("self.add(x, y);"), None);
+    }
+
+    #[test]
+    fn test_binary_operands() {
+        fn operands<'a>(source: &'a str, operator: &str) -> Option<(&'a str, &'a str)> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.text(source) == operator)
+                .unwrap();
+            binary_operands(source, &lexemes, index)
+                .map(|(left, right)| (&source[left], &source[right]))
+        }
+
+        assert_eq!(operands("let c = a - b;", "-"), Some(("a", "b")));
+        assert_eq!(
+            operands("assert(self.x[i] / f(y, z) == 1);", "/"),
+            Some(("self.x[i]", "f(y, z)"))
+        );
+        assert_eq!(
+            operands("if std::ops::x() % 2 != 0 { }", "%"),
+            Some(("std::ops::x()", "2"))
+        );
+        assert_eq!(
+            operands("let c = (a + 1) >> b;", ">>"),
+            Some(("(a + 1)", "b"))
+        );
+        // The operands of a tighter or equally tight neighbour aren't this operator's
+        assert_eq!(operands("let c = a * b - c;", "-"), None);
+        assert_eq!(operands("let c = a - b * c;", "-"), None);
+        assert_eq!(operands("let c = a - b + c;", "-"), Some(("a", "b")));
+        assert_eq!(operands("let c = a + b - c;", "-"), None);
+        assert_eq!(operands("let c = a - b as u8;", "-"), None);
+        assert_eq!(operands("let c = -a - b;", "-"), None);
+    }
+
+    #[test]
+    fn test_index_span() {
+        fn indexes(source: &str) -> Vec<&str> {
+            let lexemes = lex(source);
+            (0..lexemes.len())
+                .filter_map(|i| index_span(source, &lexemes, i))
+                .map(|span| &source[span])
+                .collect()
+        }
+
+        assert_eq!(
+            indexes("let a = arr[0] + m[i][j + 1];"),
+            ["0", "i", "j + 1"]
+        );
+        assert_eq!(indexes("let b = f(x)[2];"), ["2"]);
+        assert_eq!(indexes("let a: [u8; 2] = [0; 2];"), Vec::<&str>::new());
+        assert_eq!(
+            indexes("#[test] fn t() { let [x, y] = p; }"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn test_is_field_access() {
+        let source = "p.x = self.inner.y + p.len() + q.0;";
+        let lexemes = lex(source);
+        let fields: Vec<&str> = (0..lexemes.len())
+            .filter(|&i| is_field_access(source, &lexemes, i))
+            .map(|i| lexemes[i].text(source))
+            .collect();
+        assert_eq!(fields, ["x", "inner", "y"]);
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let source =
+            "struct Point<T> { x: Field, pub y: Field, z: [u8; 2], w: Vec<(u8, T)> }\nstruct Unit;";
+        let fields = struct_fields(source, &lex(source));
+        assert_eq!(
+            fields,
+            vec![vec![
+                ("x".to_string(), "Field".to_string()),
+                ("y".to_string(), "Field".to_string()),
+                ("z".to_string(), "[u8; 2]".to_string()),
+                ("w".to_string(), "Vec<(u8, T)>".to_string())
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_default_impls() {
+        let source = "impl Default for Point { }
+            impl<T> std::default::Default for shapes::Square<T> where T: Eq { }
+            impl Eq for Line { } fn f() { for x in xs { } }";
+        assert_eq!(default_impls(source, &lex(source)), vec!["Point", "Square"]);
+    }
+
+    #[test]
+    fn test_constraint_span() {
+        fn constraint<'a>(source: &'a str, keyword: &str) -> Option<&'a str> {
+            let lexemes = lex(source);
+            let index = lexemes
+                .iter()
+                .position(|l| l.text(source) == keyword)
+                .unwrap();
+            constraint_span(source, &lexemes, index).map(|span| &source[span])
+        }
+
+        assert_eq!(
+            constraint("fn main() { assert(x != y); let a = 1; }", "assert"),
+            Some("assert(x != y);")
+        );
+        assert_eq!(
+            constraint(
+                "{ let a = 1; assert_eq(f(a), [1, 2], \"msg\") }",
+                "assert_eq"
+            ),
+            Some("assert_eq(f(a), [1, 2], \"msg\")")
+        );
+        assert_eq!(
+            constraint("{ constrain x == y; }", "constrain"),
+            Some("constrain x == y;")
+        );
+        assert_eq!(constraint("let ok = assert(x);", "assert"), None);
+        assert_eq!(constraint("{ foo.assert(x); }", "assert"), None);
+    }
+
+    #[test]
+    fn test_statement_span() {
+        fn statements(source: &str) -> Vec<&str> {
+            let lexemes = lex(source);
+            (0..lexemes.len())
+                .filter_map(|i| statement_span(source, &lexemes, i))
+                .map(|span| &source[span])
+                .collect()
+        }
+
+        assert_eq!(
+            statements("fn main() { let mut c = x; c = 6; c += f(1); v.push(c); c }"),
+            vec!["c = 6;", "c += f(1);", "v.push(c);"]
+        );
+        assert_eq!(
+            statements("fn main() { if x { a[0] = 1; } else { foo::bar(|y| { y }); } }"),
+            vec!["a[0] = 1;", "foo::bar(|y| { y });"]
+        );
+        // Bindings, constraints, returns and bare expressions are left alone
+        assert!(statements("fn f() { let a = g(); assert(a); return a; a + 1; b }").is_empty());
+        // Block statements are never joined with the statement after them
+        assert_eq!(
+            statements("fn main() { unsafe { log(x); }\n    c = 5; }"),
+            vec!["log(x);", "c = 5;"]
+        );
+        assert_eq!(
+            statements("fn main() { while i < 3 { i += 1; }\n    c = c + 1; }"),
+            vec!["i += 1;", "c = c + 1;"]
+        );
+        assert_eq!(
+            statements("fn main() { loop { break; } match x { _ => {} } c = 1; }"),
+            vec!["c = 1;"]
+        );
+    }
+
+    #[test]
+    fn test_return_types_and_tails() {
+        fn signature(source: &str) -> (Option<String>, Option<&str>) {
+            let tree = tree(source);
+            let function = tree.functions()[0];
+            (
+                function.return_type().map(str::to_string),
+                function.tail().map(|span| &source[span]),
+            )
+        }
+
+        assert_eq!(
+            signature("fn f(a: Field) -> pub Field { let b = a; a + b }"),
+            (Some("Field".to_string()), Some("a + b"))
+        );
+        assert_eq!(
+            signature("fn f(g: fn(u8) -> u8) -> [u8; 2] where T: Eq { [g(1), 2] }"),
+            (Some("[u8; 2]".to_string()), Some("[g(1), 2]"))
+        );
+        assert_eq!(
+            signature("fn f(a: u8) -> bool { for i in 0..3 { } if a > 1 { true } else { false } }"),
+            (
+                Some("bool".to_string()),
+                Some("if a > 1 { true } else { false }")
+            )
+        );
+        assert_eq!(
+            signature("fn f() -> Point { Point { x: 1 } }"),
+            (Some("Point".to_string()), Some("Point { x: 1 }"))
+        );
+        assert_eq!(
+            signature("fn main() -> call_data(0) u32 { 1 }"),
+            (Some("u32".to_string()), Some("1"))
+        );
+        assert_eq!(
+            signature("fn f() -> u8 { return 1; }"),
+            (Some("u8".to_string()), None)
+        );
+        assert_eq!(signature("fn f() { g() }"), (None, Some("g()")));
+    }
+
+    #[test]
+    fn test_is_cast_type() {
+        let source = "let a = (c as u64) + (x as Field) + (y as T) + (z as u8::Max); let u32 = 1;";
+        let lexemes = lex(source);
+        let casts: Vec<&str> = (0..lexemes.len())
+            .filter(|&i| is_cast_type(source, &lexemes, i))
+            .map(|i| lexemes[i].text(source))
+            .collect();
+        assert_eq!(casts, vec!["u64", "Field"]);
+    }
+
+    #[test]
+    fn test_is_cast_operand() {
+        fn operands(source: &str) -> Vec<bool> {
+            let lexemes = lex(source);
+            (0..lexemes.len())
+                .filter(|&i| is_cast_type(source, &lexemes, i))
+                .map(|i| is_cast_operand(source, &lexemes, i))
+                .collect()
+        }
+
+        assert_eq!(operands("assert(c as u64 > x as u64);"), vec![true, true]);
+        assert_eq!(operands("let a = b + f(x) as u8;"), vec![true]);
+        assert_eq!(operands("let a = ((c as u64)) * 2;"), vec![true]);
+        assert_eq!(operands("let a = f(c as u64) * 2;"), vec![false]);
+        assert_eq!(
+            operands("let a = x as u8; a += y as u8; g(z as Field);"),
+            vec![false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_literal_is_mutable() {
+        fn literals(source: &str) -> Vec<&str> {
+            let lexemes = lex(source);
+            lexemes
+                .iter()
+                .enumerate()
+                .filter(|(i, l)| {
+                    l.kind() == LexemeKind::Int
+                        && !l.in_type()
+                        && literal_is_mutable(source, &lexemes, *i)
+                })
+                .map(|(_, l)| l.text(source))
+                .collect()
+        }
+
+        assert_eq!(literals("assert(x < 64);"), vec!["64"]);
+        assert_eq!(literals("let a: [u8; 32] = [0; 32];"), vec!["0"]);
+        assert_eq!(literals("let b = t.0 + 0xff;"), vec!["0xff"]);
+        assert_eq!(literals("let c = foo::<3>(1);"), vec!["1"]);
+    }
+
+    #[test]
+    fn test_comptime_blocks() {
+        let source = "fn main() { let x = comptime { 1 + 2 }; x * 3 }";
+        let tree = tree(source);
+        let enclosing = tree.enclosing(source.find('+').unwrap());
+        assert_eq!(enclosing.len(), 2);
+        assert_eq!(enclosing[1].kind(), ItemKind::Comptime);
+        assert_eq!(tree.item_path(&[], source.find('+').unwrap()), "main");
+    }
+}
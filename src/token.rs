@@ -1,6 +1,5 @@
 use rand::seq::SliceRandom;
-use regex::Regex;
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct MetaToken {
@@ -8,6 +7,9 @@ pub struct MetaToken {
     span: (u32, u32),
     src: Box<PathBuf>,
     id: u32,
+    function: Option<String>,
+    item_path: Option<String>,
+    predicate: Option<(u32, u32)>,
 }
 
 impl MetaToken {
@@ -17,6 +19,9 @@ impl MetaToken {
             span,
             src,
             id,
+            function: None,
+            item_path: None,
+            predicate: None,
         }
     }
 
@@ -47,6 +52,30 @@ impl MetaToken {
     pub fn span_end(&self) -> u32 {
         self.span.1
     }
+
+    /// The name of the function the token is in, if any
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+
+    /// The path of the innermost item the token is in, i.e: `my_crate::math::add`
+    pub fn item_path(&self) -> Option<&str> {
+        self.item_path.as_deref()
+    }
+
+    pub fn set_item(&mut self, function: Option<String>, item_path: Option<String>) {
+        self.function = function;
+        self.item_path = item_path;
+    }
+
+    /// The span of the whole predicate a relational operator is part of, i.e: `a + 1 < b`
+    pub fn predicate(&self) -> Option<(u32, u32)> {
+        self.predicate
+    }
+
+    pub fn set_predicate(&mut self, predicate: (u32, u32)) {
+        self.predicate = Some(predicate);
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -113,46 +142,67 @@ pub enum Token {
     DoubleAmpersand,
     /// !
     Bang,
+    /// true, as a literal or replacing a whole predicate or condition
+    True,
+    /// false, as a literal or replacing a whole predicate or condition
+    False,
+    /// An integer or Field literal, i.e: 64 or 0x40
+    Literal(String),
+    /// The condition of an `if` or `assert`, i.e: x == 0 in `if x == 0 { .. }`
+    Condition(String),
+    /// A whole `assert`, `assert_eq` or `constrain` statement, i.e: `assert(x != y);`
+    Constraint(String),
+    /// An assignment or call statement, i.e: `c = 6;` or `v.push(x);`
+    Statement(String),
+    /// A function's tail expression and its return type, i.e: `a + b` and `Field`
+    ReturnValue(String, String),
+    /// The integer or Field type of a cast, i.e: `u64` in `c as u64`
+    Cast(String),
+    /// The type of a cast that is an operand of a binary operator, i.e: `u64` in
+    /// `c as u64 > x as u64`. It is narrowed inside the cast, i.e: `c as u32 as u64`, so that
+    /// both operands keep their type
+    CastOperand(String),
+    /// `..`, the exclusive range of a `for` loop
+    DotDot,
+    /// `..=`, the inclusive range of a `for` loop
+    DotDotEqual,
+    /// A bound of a `for` loop's range, i.e: `n` in `for i in 0..n { .. }`
+    RangeBound(String),
+    /// A call to an `unconstrained fn` from constrained code and the type it returns,
+    /// i.e: `sqrt_hint(x)` and `Field`
+    Hint(String, String),
+    /// Two adjacent call arguments of the same type, or the operands of a non-commutative
+    /// operator, and the same text with them swapped, i.e: `x, y` and `y, x` or `a - b` and `b - a`
+    Swap(String, String),
+    /// The index expression of an array access, i.e: `i` in `arr[i]`
+    Index(String),
+    /// A struct field access and another field of the same type, i.e: `x` and `y` in `p.x`
+    FieldAccess(String, String),
     /// empty
     Void,
 }
 
-pub fn token_regexes() -> Vec<Regex> {
-    vec![
-        r" (==) ",
-        r" (!=) ",
-        r"(![^=\s])",
-        r" (<) ",
-        r" (<=) ",
-        r" (>) ",
-        r" (>=) ",
-        r" (&) ",
-        r" (\|) ",
-        r" (<<) ",
-        r" (>>) ",
-        r" (\+) ",
-        r" (-) ",
-        r" (\*) ",
-        r" (/) ",
-        r" (%) ",
-        r" (\+\+) ",
-        r" (--) ",
-        r" (\+=) ",
-        r" (-=) ",
-        r" (\*=) ",
-        r" (/=) ",
-        r" (%=) ",
-        r" (&=) ",
-        r" (\|=) ",
-        r" (^=) ",
-        r" (<<=) ",
-        r" (>>=) ",
-        r" (\|\|) ",
-        r" (&&) ",
-    ]
-    .into_iter()
-    .map(|pattern| Regex::new(pattern).unwrap())
-    .collect()
+impl Token {
+    /// Whether swapping the operator's operands changes its result, i.e: `-` or `<<`
+    pub fn is_non_commutative(&self) -> bool {
+        matches!(
+            self,
+            Token::Minus | Token::Slash | Token::Percent | Token::ShiftLeft | Token::ShiftRight
+        )
+    }
+
+    /// Whether the token compares its operands, i.e: `<` or `==`
+    pub fn is_relational(&self) -> bool {
+        matches!(
+            self,
+            Token::Equal
+                | Token::NotEqual
+                | Token::Less
+                | Token::LessEqual
+                | Token::Greater
+                | Token::GreaterEqual
+        )
+    }
 }
 
 pub fn raw_string_as_token(raw: &str) -> Option<Token> {
@@ -234,11 +284,9 @@ pub fn all_tokens() -> Vec<Token> {
     ]
 }
 
-pub fn random_token() -> Token {
-    all_tokens()
-        .choose(&mut rand::thread_rng())
-        .unwrap()
-        .clone()
+/// One of the replacements, picked at random
+pub fn random_replacement(replacements: &[Token]) -> Option<Token> {
+    replacements.choose(&mut rand::thread_rng()).cloned()
 }
 
 pub fn token_transformer(token: Token) -> Option<Token> {
@@ -278,7 +326,341 @@ pub fn token_transformer(token: Token) -> Option<Token> {
     }
 }
 
-pub fn token_as_bytes<'a>(token: &Token) -> Option<&'a [u8]> {
+/// How many mutants to generate per mutation site
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum MutationSet {
+    /// One mutant per operator, see `token_transformer()`
+    #[default]
+    Minimal,
+    /// Every sensible replacement of each operator, see `token_replacements()`
+    Exhaustive,
+}
+
+impl MutationSet {
+    pub fn list() -> String {
+        ["minimal", "exhaustive"].join(", ")
+    }
+}
+
+impl FromStr for MutationSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minimal" => Ok(MutationSet::Minimal),
+            "exhaustive" => Ok(MutationSet::Exhaustive),
+            _ => Err(format!(
+                "No matching mutation set. Current supported sets are: {}",
+                MutationSet::list()
+            )),
+        }
+    }
+}
+
+/// The replacements of a token in the given mutation set.
+/// `Token::True` and `Token::False` replace the whole predicate a relational operator is part of.
+///
+/// Tokens carrying code have the same replacements in both sets:
+/// - literals: `0`, `1`, `n + 1` and `n - 1`
+/// - `true` and `false`: flipped
+/// - conditions: negated, `true` and `false`
+/// - constraints and statements: deleted
+/// - return values: their type's default
+/// - casts: narrowed and widened
+/// - `for` loop ranges and array indexes: shifted by one
+/// - unconstrained call results: tampered with
+/// - arguments, operands and struct fields: swapped
+pub fn token_replacements(token: &Token, mutation_set: MutationSet) -> Vec<Token> {
+    match token {
+        Token::Literal(text) => {
+            return literal_replacements(text)
+                .into_iter()
+                .map(Token::Literal)
+                .collect();
+        }
+        Token::Constraint(_) | Token::Statement(_) => return vec![Token::Void],
+        Token::Cast(cast_type) => {
+            return cast_replacements(cast_type)
+                .into_iter()
+                .map(Token::Cast)
+                .collect();
+        }
+        Token::CastOperand(cast_type) => {
+            return narrower_casts(cast_type)
+                .into_iter()
+                .map(|narrower| Token::CastOperand(format!("{} as {}", narrower, cast_type)))
+                .collect();
+        }
+        Token::ReturnValue(value, return_type) => {
+            let default = default_value(return_type);
+            return match *value == default {
+                true => vec![],
+                false => vec![Token::ReturnValue(default, return_type.clone())],
+            };
+        }
+        Token::RangeBound(text) => {
+            return shifted_bounds(text)
+                .into_iter()
+                .map(Token::RangeBound)
+                .collect();
+        }
+        Token::Hint(call, return_type) => {
+            return hint_replacements(call, return_type)
+                .into_iter()
+                .map(|hint| Token::Hint(hint, return_type.clone()))
+                .collect();
+        }
+        Token::Index(text) => {
+            return shifted_bounds(text).into_iter().map(Token::Index).collect();
+        }
+        Token::FieldAccess(field, other) => {
+            return vec![Token::FieldAccess(other.clone(), field.clone())]
+        }
+        Token::Swap(text, swapped) => return vec![Token::Swap(swapped.clone(), text.clone())],
+        Token::DotDot => return vec![Token::DotDotEqual],
+        Token::DotDotEqual => return vec![Token::DotDot],
+        Token::True => return vec![Token::False],
+        Token::False => return vec![Token::True],
+        Token::Condition(text) => {
+            return [
+                Token::Condition(format!("!({})", text)),
+                Token::True,
+                Token::False,
+            ]
+            .into_iter()
+            .filter(|mutation| token_as_bytes(mutation) != Some(text.as_bytes()))
+            .collect();
+        }
+        _ => {}
+    }
+
+    if mutation_set == MutationSet::Minimal {
+        return token_transformer(token.clone()).into_iter().collect();
+    }
+
+    let family: &[Token] = match token {
+        Token::Equal
+        | Token::NotEqual
+        | Token::Less
+        | Token::LessEqual
+        | Token::Greater
+        | Token::GreaterEqual => &[
+            Token::Less,
+            Token::LessEqual,
+            Token::Greater,
+            Token::GreaterEqual,
+            Token::Equal,
+            Token::NotEqual,
+            Token::True,
+            Token::False,
+        ],
+        Token::Plus | Token::Minus | Token::Star | Token::Slash | Token::Percent => &[
+            Token::Plus,
+            Token::Minus,
+            Token::Star,
+            Token::Slash,
+            Token::Percent,
+        ],
+        Token::Ampersand | Token::Pipe | Token::Caret => {
+            &[Token::Ampersand, Token::Pipe, Token::Caret]
+        }
+        Token::ShiftLeft | Token::ShiftRight => &[Token::ShiftLeft, Token::ShiftRight],
+        Token::Increment | Token::Decrement => &[Token::Increment, Token::Decrement],
+        Token::PlusEquals
+        | Token::MinusEquals
+        | Token::StarEquals
+        | Token::SlashEquals
+        | Token::PercentEquals => &[
+            Token::PlusEquals,
+            Token::MinusEquals,
+            Token::StarEquals,
+            Token::SlashEquals,
+            Token::PercentEquals,
+        ],
+        Token::AmpersandEquals | Token::PipeEquals | Token::CaretEquals => &[
+            Token::AmpersandEquals,
+            Token::PipeEquals,
+            Token::CaretEquals,
+        ],
+        Token::ShiftLeftEquals | Token::ShiftRightEquals => {
+            &[Token::ShiftLeftEquals, Token::ShiftRightEquals]
+        }
+        Token::DoublePipe | Token::DoubleAmpersand => &[Token::DoublePipe, Token::DoubleAmpersand],
+        Token::Bang => &[Token::Void],
+        Token::True
+        | Token::False
+        | Token::Literal(_)
+        | Token::Condition(_)
+        | Token::Constraint(_)
+        | Token::Statement(_)
+        | Token::ReturnValue(..)
+        | Token::Cast(_)
+        | Token::CastOperand(_)
+        | Token::DotDot
+        | Token::DotDotEqual
+        | Token::RangeBound(_)
+        | Token::Hint(..)
+        | Token::Swap(..)
+        | Token::Index(_)
+        | Token::FieldAccess(..)
+        | Token::Void => &[],
+    };
+
+    family.iter().filter(|t| *t != token).cloned().collect()
+}
+
+/// Whether `text` is a decimal or hex integer literal, i.e: `1_000` or `0xff`
+pub fn is_int_literal(text: &str) -> bool {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    let radix = if digits.len() < text.len() { 16 } else { 10 };
+    digits.chars().any(|c| c.is_digit(radix))
+        && digits.chars().all(|c| c.is_digit(radix) || c == '_')
+}
+
+/// `0`, `1`, `n + 1` and `n - 1` for an integer literal `n`, leaving out `n` itself.
+/// Hex literals stay in hex. Literals too large for a u128 only get `0` and `1`.
+pub fn literal_replacements(text: &str) -> Vec<String> {
+    let (digits, radix) = match text.strip_prefix("0x") {
+        Some(digits) => (digits, 16),
+        None => (text, 10),
+    };
+    let format = |n: u128| match radix {
+        16 => format!("0x{:x}", n),
+        _ => n.to_string(),
+    };
+
+    let mut values = vec![0, 1];
+    if let Ok(n) = u128::from_str_radix(&digits.replace('_', ""), radix) {
+        values.retain(|&v| v != n);
+        values.extend(n.checked_add(1));
+        values.extend(n.checked_sub(1));
+    }
+
+    let mut replacements: Vec<String> = vec![];
+    for value in values {
+        let replacement = if value < 2 {
+            value.to_string()
+        } else {
+            format(value)
+        };
+        if !replacements.contains(&replacement) {
+            replacements.push(replacement);
+        }
+    }
+    replacements
+}
+
+/// A range bound or array index shifted by one: `n + 1` and `n - 1` for an integer literal `n`, or the
+/// bound with `+ 1` and `- 1` appended, i.e: `(n - 1) + 1` and `(n - 1) - 1` for `n - 1`.
+/// Hex literals stay in hex.
+pub fn shifted_bounds(text: &str) -> Vec<String> {
+    if is_int_literal(text) {
+        let (digits, radix) = match text.strip_prefix("0x") {
+            Some(digits) => (digits, 16),
+            None => (text, 10),
+        };
+        let Ok(n) = u128::from_str_radix(&digits.replace('_', ""), radix) else {
+            return vec![];
+        };
+        return [n.checked_add(1), n.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(|value| match radix {
+                16 => format!("0x{:x}", value),
+                _ => value.to_string(),
+            })
+            .collect();
+    }
+
+    let compound = text
+        .chars()
+        .any(|c| c.is_whitespace() || "+-*/%&|^<>!".contains(c));
+    let bound = match compound {
+        true => format!("({})", text),
+        false => text.to_string(),
+    };
+    vec![format!("{} + 1", bound), format!("{} - 1", bound)]
+}
+
+/// Whether `text` is a Noir integer type, i.e: `u64` or `i8`
+pub fn is_int_type(text: &str) -> bool {
+    text.strip_prefix(['u', 'i'])
+        .is_some_and(|bits| !bits.is_empty() && bits.chars().all(|c| c.is_ascii_digit()))
+}
+
+const UNSIGNED_TYPES: [&str; 7] = ["u1", "u8", "u16", "u32", "u64", "u128", "Field"];
+const SIGNED_TYPES: [&str; 5] = ["i8", "i16", "i32", "i64", "Field"];
+
+/// The types a cast to `cast_type` is changed to: the next narrower type, the narrowest
+/// 8-bit type, the next wider type and `Field`, i.e: `u32`, `u8`, `u128` and `Field` for `u64`.
+/// A cast to `Field` is narrowed to `u64` and `u8`.
+pub fn cast_replacements(cast_type: &str) -> Vec<String> {
+    let widths: &[&str] = if cast_type.starts_with('i') {
+        &SIGNED_TYPES
+    } else {
+        &UNSIGNED_TYPES
+    };
+
+    let candidates = match widths.iter().position(|t| *t == cast_type) {
+        Some(i) if cast_type != "Field" => vec![
+            i.checked_sub(1).map(|n| widths[n]),
+            widths.iter().find(|t| t.ends_with('8')).copied(),
+            widths.get(i + 1).copied(),
+            Some("Field"),
+        ],
+        Some(_) => vec![Some("u64"), Some("u8")],
+        // Integer types Noir doesn't have, i.e: `u7`, are only widened to a Field
+        None => vec![Some("Field")],
+    };
+
+    let mut replacements: Vec<String> = vec![];
+    for candidate in candidates.into_iter().flatten() {
+        if candidate != cast_type && !replacements.iter().any(|r| r == candidate) {
+            replacements.push(candidate.to_string());
+        }
+    }
+    replacements
+}
+
+/// The types of `cast_replacements` that are narrower than `cast_type`, i.e: `u32` and `u8` for
+/// `u64`, or `u64` and `u8` for `Field`
+pub fn narrower_casts(cast_type: &str) -> Vec<String> {
+    let widths: &[&str] = if cast_type.starts_with('i') {
+        &SIGNED_TYPES
+    } else {
+        &UNSIGNED_TYPES
+    };
+    let width = |t: &str| widths.iter().position(|w| *w == t);
+
+    cast_replacements(cast_type)
+        .into_iter()
+        .filter(|t| matches!((width(t), width(cast_type)), (Some(a), Some(b)) if a < b))
+        .collect()
+}
+
+/// What the result of an unconstrained call is replaced with: the result off by one and `0` for
+/// integers and `Field`s, the result negated and `false` for a `bool`, or the type's default.
+pub fn hint_replacements(call: &str, return_type: &str) -> Vec<String> {
+    let default = default_value(return_type);
+    match return_type {
+        "Field" => vec![format!("({} + 1)", call), default],
+        "bool" => vec![format!("!{}", call), default],
+        _ if is_int_type(return_type) => vec![format!("({} + 1)", call), default],
+        _ => vec![default],
+    }
+}
+
+/// The default value of a Noir type, i.e: `0` for `Field` and `u64` or `false` for `bool`
+pub fn default_value(noir_type: &str) -> String {
+    match noir_type {
+        "Field" => "0".to_string(),
+        "bool" => "false".to_string(),
+        _ if is_int_type(noir_type) => "0".to_string(),
+        _ => "std::default::Default::default()".to_string(),
+    }
+}
+
+pub fn token_as_bytes(token: &Token) -> Option<&[u8]> {
     match token {
         Token::Equal => Some(b"=="),
         Token::NotEqual => Some(b"!="),
@@ -311,6 +693,22 @@ pub fn token_as_bytes<'a>(token: &Token) -> Option<&'a [u8]> {
         Token::DoublePipe => Some(b"||"),
         Token::DoubleAmpersand => Some(b"&&"),
         Token::Bang => Some(b"!"),
+        Token::True => Some(b"true"),
+        Token::False => Some(b"false"),
+        Token::Literal(text)
+        | Token::Condition(text)
+        | Token::Constraint(text)
+        | Token::Statement(text)
+        | Token::ReturnValue(text, _)
+        | Token::Hint(text, _)
+        | Token::Swap(text, _)
+        | Token::Index(text)
+        | Token::FieldAccess(text, _)
+        | Token::Cast(text)
+        | Token::CastOperand(text)
+        | Token::RangeBound(text) => Some(text.as_bytes()),
+        Token::DotDot => Some(b".."),
+        Token::DotDotEqual => Some(b"..="),
         Token::Void => Some(b""),
     }
 }
@@ -319,6 +717,7 @@ pub fn token_as_bytes<'a>(token: &Token) -> Option<&'a [u8]> {
 mod tests {
     use super::*;
     use crate::handlers::mutator::{mutant_builder, MutationStatus};
+    use crate::operators::{OperatorConfig, OperatorFamily};
     use std::path::PathBuf;
 
     #[test]
@@ -999,7 +1398,16 @@ mod tests {
         let token = Token::Equal;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::NotEqual);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1016,7 +1424,16 @@ mod tests {
         let token = Token::NotEqual;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Equal);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1033,7 +1450,16 @@ mod tests {
         let token = Token::Greater;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::LessEqual);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1050,7 +1476,16 @@ mod tests {
         let token = Token::GreaterEqual;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Less);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1067,7 +1502,16 @@ mod tests {
         let token = Token::Less;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::GreaterEqual);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1084,7 +1528,16 @@ mod tests {
         let token = Token::LessEqual;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Greater);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1101,7 +1554,16 @@ mod tests {
         let token = Token::Ampersand;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Pipe);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1118,7 +1580,16 @@ mod tests {
         let token = Token::Pipe;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Ampersand);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1135,7 +1606,16 @@ mod tests {
         let token = Token::Caret;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Ampersand);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1152,7 +1632,16 @@ mod tests {
         let token = Token::Plus;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Minus);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1169,7 +1658,16 @@ mod tests {
         let token = Token::Minus;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Plus);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1186,7 +1684,16 @@ mod tests {
         let token = Token::Star;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Slash);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1203,7 +1710,16 @@ mod tests {
         let token = Token::Slash;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Star);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1219,7 +1735,16 @@ mod tests {
         let token = Token::Percent;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.mutation(), Token::Star);
         let bytes_str =
             String::from_utf8(mutant.bytes().clone()).expect("Failed to convert bytes to string");
@@ -1236,7 +1761,16 @@ mod tests {
         let token = Token::Increment;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1249,7 +1783,16 @@ mod tests {
         let token = Token::Decrement;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1262,7 +1805,16 @@ mod tests {
         let token = Token::PlusEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1275,7 +1827,16 @@ mod tests {
         let token = Token::MinusEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1288,7 +1849,16 @@ mod tests {
         let token = Token::StarEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1301,7 +1871,16 @@ mod tests {
         let token = Token::SlashEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1314,7 +1893,16 @@ mod tests {
         let token = Token::PercentEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1327,7 +1915,16 @@ mod tests {
         let token = Token::AmpersandEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1340,7 +1937,16 @@ mod tests {
         let token = Token::PipeEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
@@ -1353,7 +1959,16 @@ mod tests {
         let token = Token::CaretEquals;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
@@ -1367,7 +1982,16 @@ mod tests {
         let token = Token::ShiftLeftEquals;
         let span = (0, 3);
         let id = 42;
-        let mutant = mutant_builder(id, token.clone(), span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token.clone(),
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.id(), id);
         assert_eq!(mutant.mutation(), token_transformer(token.clone()).unwrap());
@@ -1387,7 +2011,16 @@ mod tests {
         let token = Token::ShiftRightEquals;
         let span = (0, 3);
         let id = 42;
-        let mutant = mutant_builder(id, token, span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token,
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.span_start(), span.0);
         assert_eq!(mutant.span_end(), span.1);
@@ -1401,7 +2034,16 @@ mod tests {
         let token = Token::DoublePipe;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token.clone(), span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token.clone(),
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.id(), id);
         assert_eq!(mutant.mutation(), token_transformer(token.clone()).unwrap());
@@ -1421,7 +2063,16 @@ mod tests {
         let token = Token::DoubleAmpersand;
         let span = (0, 2);
         let id = 42;
-        let mutant = mutant_builder(id, token.clone(), span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token.clone(),
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.id(), id);
         assert_eq!(mutant.mutation(), token_transformer(token.clone()).unwrap());
@@ -1441,7 +2092,16 @@ mod tests {
         let token = Token::Bang;
         let span = (0, 1);
         let id = 42;
-        let mutant = mutant_builder(id, token.clone(), span, path.clone(), false).unwrap();
+        let mutant = mutant_builder(
+            id,
+            token.clone(),
+            span,
+            path.clone(),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0);
 
         assert_eq!(mutant.id(), id);
         assert_eq!(mutant.mutation(), token_transformer(token.clone()).unwrap());
@@ -1453,4 +2113,281 @@ mod tests {
         assert_eq!(mutant.span_end(), span.1);
         assert_eq!(mutant.path(), path);
     }
+
+    #[test]
+    fn test_token_replacements_minimal() {
+        assert_eq!(
+            token_replacements(&Token::Less, MutationSet::Minimal),
+            vec![Token::GreaterEqual]
+        );
+    }
+
+    #[test]
+    fn test_token_replacements_exhaustive() {
+        assert_eq!(
+            token_replacements(&Token::Less, MutationSet::Exhaustive),
+            vec![
+                Token::LessEqual,
+                Token::Greater,
+                Token::GreaterEqual,
+                Token::Equal,
+                Token::NotEqual,
+                Token::True,
+                Token::False,
+            ]
+        );
+        assert_eq!(
+            token_replacements(&Token::Plus, MutationSet::Exhaustive),
+            vec![Token::Minus, Token::Star, Token::Slash, Token::Percent]
+        );
+        assert_eq!(
+            token_replacements(&Token::Bang, MutationSet::Exhaustive),
+            vec![Token::Void]
+        );
+    }
+
+    #[test]
+    fn test_literal_replacements() {
+        assert_eq!(literal_replacements("64"), vec!["0", "1", "65", "63"]);
+        assert_eq!(literal_replacements("0"), vec!["1"]);
+        assert_eq!(literal_replacements("1"), vec!["0", "2"]);
+        assert_eq!(literal_replacements("2"), vec!["0", "1", "3"]);
+        assert_eq!(literal_replacements("0x40"), vec!["0", "1", "0x41", "0x3f"]);
+        assert_eq!(literal_replacements("1_000"), vec!["0", "1", "1001", "999"]);
+        assert_eq!(
+            literal_replacements(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            ),
+            vec!["0", "1"]
+        );
+        assert_eq!(
+            token_replacements(&Token::Literal("7".to_string()), MutationSet::Minimal),
+            vec![
+                Token::Literal("0".to_string()),
+                Token::Literal("1".to_string()),
+                Token::Literal("8".to_string()),
+                Token::Literal("6".to_string()),
+            ]
+        );
+        assert!(is_int_literal("0xff"));
+        assert!(is_int_literal("1_000"));
+        assert!(!is_int_literal("0x"));
+        assert!(!is_int_literal("x1"));
+    }
+
+    #[test]
+    fn test_condition_and_boolean_replacements() {
+        assert_eq!(
+            token_replacements(
+                &Token::Condition("x == 0".to_string()),
+                MutationSet::Minimal
+            ),
+            vec![
+                Token::Condition("!(x == 0)".to_string()),
+                Token::True,
+                Token::False
+            ]
+        );
+        assert_eq!(
+            token_replacements(&Token::Condition("true".to_string()), MutationSet::Minimal),
+            vec![Token::Condition("!(true)".to_string()), Token::False]
+        );
+        assert_eq!(
+            token_replacements(&Token::True, MutationSet::Exhaustive),
+            vec![Token::False]
+        );
+
+        // A flipped literal keeps its own span, even next to a predicate
+        let mutants = mutant_builder(
+            0,
+            Token::False,
+            (4, 9),
+            PathBuf::from("test.noir"),
+            false,
+            Some((0, 12)),
+            &OperatorConfig::default(),
+        );
+        assert_eq!(mutants[0].span(), (4, 9));
+        assert_eq!(mutants[0].bytes(), b"true");
+    }
+
+    #[test]
+    fn test_return_value_replacements() {
+        let return_value =
+            |value: &str, return_type: &str| Token::ReturnValue(value.into(), return_type.into());
+        assert_eq!(
+            token_replacements(&return_value("a + b", "Field"), MutationSet::Minimal),
+            vec![return_value("0", "Field")]
+        );
+        assert_eq!(
+            token_replacements(&return_value("a > b", "bool"), MutationSet::Minimal),
+            vec![return_value("false", "bool")]
+        );
+        assert_eq!(
+            token_replacements(
+                &return_value("Point { x: 1 }", "Point"),
+                MutationSet::Exhaustive
+            ),
+            vec![return_value("std::default::Default::default()", "Point")]
+        );
+        assert!(token_replacements(&return_value("0", "u64"), MutationSet::Minimal).is_empty());
+        assert!(is_int_type("u64"));
+        assert!(is_int_type("i8"));
+        assert!(!is_int_type("u"));
+        assert!(!is_int_type("usize_"));
+    }
+
+    #[test]
+    fn test_cast_replacements() {
+        assert_eq!(cast_replacements("u64"), vec!["u32", "u8", "u128", "Field"]);
+        assert_eq!(cast_replacements("u8"), vec!["u1", "u16", "Field"]);
+        assert_eq!(cast_replacements("u1"), vec!["u8", "Field"]);
+        assert_eq!(cast_replacements("u128"), vec!["u64", "u8", "Field"]);
+        assert_eq!(cast_replacements("i32"), vec!["i16", "i8", "i64", "Field"]);
+        assert_eq!(cast_replacements("i64"), vec!["i32", "i8", "Field"]);
+        assert_eq!(cast_replacements("Field"), vec!["u64", "u8"]);
+        assert_eq!(
+            token_replacements(&Token::Cast("u16".to_string()), MutationSet::Minimal),
+            vec![
+                Token::Cast("u8".to_string()),
+                Token::Cast("u32".to_string()),
+                Token::Cast("Field".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_narrower_casts() {
+        assert_eq!(narrower_casts("u64"), vec!["u32", "u8"]);
+        assert_eq!(narrower_casts("u1"), Vec::<String>::new());
+        assert_eq!(narrower_casts("i32"), vec!["i16", "i8"]);
+        assert_eq!(narrower_casts("Field"), vec!["u64", "u8"]);
+        assert_eq!(narrower_casts("u7"), Vec::<String>::new());
+        assert_eq!(
+            token_replacements(&Token::CastOperand("u64".to_string()), MutationSet::Minimal),
+            vec![
+                Token::CastOperand("u32 as u64".to_string()),
+                Token::CastOperand("u8 as u64".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hint_replacements() {
+        assert_eq!(
+            hint_replacements("sqrt(x)", "Field"),
+            vec!["(sqrt(x) + 1)", "0"]
+        );
+        assert_eq!(
+            hint_replacements("lt(a, b)", "bool"),
+            vec!["!lt(a, b)", "false"]
+        );
+        assert_eq!(
+            hint_replacements("decompose(x)", "(Field, Field)"),
+            vec!["std::default::Default::default()"]
+        );
+        assert_eq!(
+            token_replacements(
+                &Token::Hint("inv(x)".to_string(), "u64".to_string()),
+                MutationSet::Minimal
+            ),
+            vec![
+                Token::Hint("(inv(x) + 1)".to_string(), "u64".to_string()),
+                Token::Hint("0".to_string(), "u64".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_replacements() {
+        let swap = Token::Swap("a - b".to_string(), "b - a".to_string());
+        assert_eq!(
+            token_replacements(&swap, MutationSet::Exhaustive),
+            vec![Token::Swap("b - a".to_string(), "a - b".to_string())]
+        );
+        assert_eq!(token_as_bytes(&swap), Some(&b"a - b"[..]));
+        assert!(Token::Minus.is_non_commutative());
+        assert!(!Token::Plus.is_non_commutative());
+    }
+
+    #[test]
+    fn test_index_and_field_replacements() {
+        assert_eq!(
+            token_replacements(&Token::Index("0".to_string()), MutationSet::Minimal),
+            vec![Token::Index("1".to_string())]
+        );
+        assert_eq!(
+            token_replacements(&Token::Index("i".to_string()), MutationSet::Minimal),
+            vec![
+                Token::Index("i + 1".to_string()),
+                Token::Index("i - 1".to_string())
+            ]
+        );
+        assert_eq!(
+            token_replacements(
+                &Token::FieldAccess("x".to_string(), "y".to_string()),
+                MutationSet::Exhaustive
+            ),
+            vec![Token::FieldAccess("y".to_string(), "x".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_shifted_bounds() {
+        assert_eq!(shifted_bounds("10"), vec!["11", "9"]);
+        assert_eq!(shifted_bounds("0"), vec!["1"]);
+        assert_eq!(shifted_bounds("0x10"), vec!["0x11", "0xf"]);
+        assert_eq!(shifted_bounds("n"), vec!["n + 1", "n - 1"]);
+        assert_eq!(
+            shifted_bounds("arr.len()"),
+            vec!["arr.len() + 1", "arr.len() - 1"]
+        );
+        assert_eq!(shifted_bounds("n - 1"), vec!["(n - 1) + 1", "(n - 1) - 1"]);
+        assert_eq!(
+            token_replacements(&Token::DotDot, MutationSet::Minimal),
+            vec![Token::DotDotEqual]
+        );
+        assert_eq!(
+            token_replacements(&Token::DotDotEqual, MutationSet::Exhaustive),
+            vec![Token::DotDot]
+        );
+    }
+
+    #[test]
+    fn test_mutant_builder_exhaustive() {
+        let path = PathBuf::from("test.noir");
+        let exhaustive =
+            OperatorConfig::new(MutationSet::Exhaustive, OperatorFamily::all(), vec![]);
+        let mutants = mutant_builder(
+            10,
+            Token::Less,
+            (9, 10),
+            path.clone(),
+            false,
+            Some((7, 12)),
+            &exhaustive,
+        );
+        assert_eq!(mutants.len(), 7);
+        assert_eq!(
+            mutants.iter().map(|m| m.id()).collect::<Vec<_>>(),
+            (10..17).collect::<Vec<_>>()
+        );
+        assert_eq!(mutants[0].span(), (9, 10));
+        assert_eq!(mutants[5].mutation(), Token::True);
+        assert_eq!(mutants[5].span(), (7, 12));
+        assert_eq!(mutants[6].bytes(), b"false");
+
+        // Without the predicate's span, only the operator is replaced
+        let mutants = mutant_builder(0, Token::Less, (9, 10), path, false, None, &exhaustive);
+        assert_eq!(mutants.len(), 5);
+    }
+
+    #[test]
+    fn test_mutation_set_from_str() {
+        assert_eq!(
+            "Exhaustive".parse::<MutationSet>(),
+            Ok(MutationSet::Exhaustive)
+        );
+        assert!("all".parse::<MutationSet>().is_err());
+    }
 }
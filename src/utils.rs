@@ -1,34 +1,168 @@
 use crate::{
+    candidates::{candidates, SourceFile},
     config::LanguageConfig,
-    token::{raw_string_as_token, token_regexes, MetaToken, Token},
+    file_manager::find_project_root,
+    syntax::{default_impls, predicate_span, struct_fields},
+    token::{is_int_type, MetaToken},
 };
 
 use std::{
     cell::Cell,
-    fs::File,
-    io::{BufReader, Read},
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufReader, Read},
     ops::Range,
     path::PathBuf,
+    process::{Child, Command, Output},
+    thread,
+    time::{Duration, Instant},
 };
 
-use regex::Regex;
-
 fn overlaps(filter: &Range<usize>, token: &Range<u32>) -> bool {
     (token.start as usize) > filter.start && (token.end as usize) < filter.end
 }
 
+/// What mutating a call site needs to know about the function it calls
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Signature {
+    pub params: Vec<String>,
+    pub return_type: Option<String>,
+    pub unconstrained: bool,
+}
+
+/// The functions and struct fields declared across a project, which call sites and field
+/// accesses are mutated with
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Declarations {
+    /// The signatures of the non-test functions, by function name
+    pub signatures: HashMap<String, Signature>,
+    /// The other fields of the same type in the field's struct, by field name
+    pub fields: HashMap<String, Vec<String>>,
+    /// The types that implement `Default`, by name
+    pub defaults: HashSet<String>,
+}
+
+impl Declarations {
+    /// Whether the type has a default value: primitives, types with an `impl Default`, and
+    /// arrays, tuples and `Option`s whose element types have one
+    pub fn has_default(&self, noir_type: &str) -> bool {
+        let noir_type = noir_type.trim();
+        if matches!(noir_type, "Field" | "bool" | "()") || is_int_type(noir_type) {
+            return true;
+        }
+        if let Some(array) = noir_type
+            .strip_prefix('[')
+            .and_then(|t| t.strip_suffix(']'))
+        {
+            return array
+                .rsplit_once(';')
+                .is_some_and(|(element, _)| self.has_default(element));
+        }
+        if let Some(tuple) = noir_type
+            .strip_prefix('(')
+            .and_then(|t| t.strip_suffix(')'))
+        {
+            return top_level_items(tuple)
+                .into_iter()
+                .all(|element| self.has_default(element));
+        }
+        let name = noir_type.split('<').next().unwrap_or_default();
+        let name = name.rsplit("::").next().unwrap_or_default();
+        name == "Option" || self.defaults.contains(name)
+    }
+}
+
+/// The comma separated items of a list, ignoring commas in nested brackets
+fn top_level_items(list: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+        .into_iter()
+        .filter(|item| !item.trim().is_empty())
+        .collect()
+}
+
+/// Collects the declarations in the files. Functions and fields are matched by name alone, so
+/// the parameters of functions declared with different parameters under the same name, and
+/// fields declared in more than one struct, are left out.
+pub fn declarations(paths: &[PathBuf], config: &dyn LanguageConfig) -> Declarations {
+    let mut signatures: HashMap<String, Signature> = HashMap::new();
+    let mut fields: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    let mut defaults = HashSet::new();
+    for path in paths {
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        let lexemes = config.lex(&contents);
+        defaults.extend(default_impls(&contents, &lexemes));
+        for struct_fields in struct_fields(&contents, &lexemes) {
+            for (name, field_type) in &struct_fields {
+                let same_type = struct_fields
+                    .iter()
+                    .filter(|(other, other_type)| other != name && other_type == field_type)
+                    .map(|(other, _)| other.clone())
+                    .collect();
+                fields
+                    .entry(name.clone())
+                    .and_modify(|siblings| *siblings = None)
+                    .or_insert(Some(same_type));
+            }
+        }
+        for function in config.syntax_tree(&contents, &lexemes).functions() {
+            if function.is_test() {
+                continue;
+            }
+            let signature = Signature {
+                params: function.params().to_vec(),
+                return_type: function.return_type().map(str::to_string),
+                unconstrained: function.is_unconstrained(),
+            };
+            signatures
+                .entry(function.name().to_string())
+                .and_modify(|existing| {
+                    if existing.params != signature.params {
+                        existing.params.clear();
+                    }
+                })
+                .or_insert(signature);
+        }
+    }
+
+    Declarations {
+        signatures,
+        fields: fields
+            .into_iter()
+            .filter_map(|(name, siblings)| Some((name, siblings?)))
+            .collect(),
+        defaults,
+    }
+}
+
+/// `declarations` are the functions and fields whose uses are mutated, see `declarations()`
 pub fn collect_tokens(
     paths: Vec<PathBuf>,
+    declarations: &Declarations,
     config: Box<dyn LanguageConfig>,
 ) -> Option<Vec<MetaToken>> {
     let mut tokens: Vec<MetaToken> = Vec::new();
-    // let language = config.language();
 
     if paths.is_empty() {
         eprintln!("No source files with unit tests found. Exiting...");
         None
     } else {
         let i = Cell::new(0);
+        let inclusive_ranges = config.supports_inclusive_ranges();
 
         for path in paths {
             let file = File::open(path.clone()).expect("Unable to open file");
@@ -36,81 +170,40 @@ pub fn collect_tokens(
             let mut contents = String::new();
             let _res = buf_reader.read_to_string(&mut contents);
 
-            let test_regex: Option<Regex> = config.test_regex();
-            let comment_regex = config.comment_regex();
-            let literal_regex = config.literal_regex();
-
-            let comment_ranges: Vec<_> = comment_regex
-                .find_iter(&contents)
-                .map(|m| m.start()..m.end())
-                .collect();
-
-            let test_ranges: Vec<_> = test_regex.as_ref().map_or(Vec::new(), |regex| {
-                regex
-                    .find_iter(&contents)
-                    .map(|m| m.start()..m.end())
-                    .collect()
-            });
-
-            let literal_ranges: Vec<_> = literal_regex
-                .find_iter(&contents)
-                .map(|m| m.start()..m.end())
-                .collect();
-
-            for regex in token_regexes() {
-                for mat in regex.captures_iter(&contents) {
-                    if mat.get(1).is_none() {
+            let lexemes = config.lex(&contents);
+            let tree = config.syntax_tree(&contents, &lexemes);
+            let test_ranges: Vec<_> = tree.test_functions().iter().map(|f| f.span()).collect();
+            let module_path = find_project_root(&path, &*config)
+                .map(|root| config.module_path(&root, &path))
+                .unwrap_or_default();
+            let file = SourceFile::new(&contents, &lexemes, &tree, declarations, inclusive_ranges);
+
+            for index in 0..lexemes.len() {
+                for (token, span) in candidates(&file, index) {
+                    let token_range = span.start as u32..span.end as u32;
+                    if test_ranges.iter().any(|r| overlaps(r, &token_range)) {
                         continue;
                     }
 
-                    let token_str = mat.get(1).unwrap().as_str();
-                    let token_range =
-                        mat.get(0).unwrap().start() as u32..mat.get(0).unwrap().end() as u32;
-
-                    if comment_ranges.iter().any(|r| overlaps(r, &token_range))
-                        || test_ranges.iter().any(|r| overlaps(r, &token_range))
-                        || literal_ranges.iter().any(|r| overlaps(r, &token_range))
-                    {
-                        continue;
-                    }
-
-                    let token_str = if token_str.starts_with('!') && token_str != "!=" {
-                        "!"
-                    } else {
-                        token_str
-                    };
-
-                    if token_str.starts_with('!') && token_str != "!=" {
-                        tokens.push(MetaToken::new(
-                            Token::Bang,
-                            (
-                                mat.get(0).unwrap().start() as u32 + 1,
-                                mat.get(0).unwrap().end() as u32,
-                            ),
-                            Box::new(path.clone()),
-                            i.get(),
-                        ));
-                    } else if token_str == "!=" {
-                        tokens.push(MetaToken::new(
-                            Token::NotEqual,
-                            (
-                                mat.get(0).unwrap().start() as u32 + 1,
-                                mat.get(0).unwrap().end() as u32,
-                            ),
-                            Box::new(path.clone()),
-                            i.get(),
-                        ));
-                    } else {
-                        tokens.push(MetaToken::new(
-                            raw_string_as_token(token_str).unwrap(),
-                            (
-                                mat.get(0).unwrap().start() as u32 + 1,
-                                mat.get(0).unwrap().end() as u32,
-                            ),
-                            Box::new(path.clone()),
-                            i.get(),
-                        ));
+                    let mut meta_token = MetaToken::new(
+                        token,
+                        (token_range.start, token_range.end),
+                        Box::new(path.clone()),
+                        i.get(),
+                    );
+                    let item_path = tree.item_path(&module_path, span.start);
+                    meta_token.set_item(
+                        tree.enclosing_function(span.start)
+                            .map(|f| f.name().to_string()),
+                        (!item_path.is_empty()).then_some(item_path),
+                    );
+                    if meta_token.token().is_relational() {
+                        if let Some(predicate) = predicate_span(&contents, &lexemes, index) {
+                            meta_token
+                                .set_predicate((predicate.start as u32, predicate.end as u32));
+                        }
                     }
+                    tokens.push(meta_token);
                     i.set(i.get() + 1);
                 }
             }
@@ -119,58 +212,95 @@ pub fn collect_tokens(
     }
 }
 
+/// Replaces the original token at `start_index` with its replacement.
+/// Fails if the bytes at `start_index` aren't the original token, i.e: the source changed since
+/// it was scanned.
 pub fn replace_bytes(
     original_bytes: &mut Vec<u8>,
     start_index: usize,
     original_token_as_bytes: &[u8],
     replacement: &[u8],
-) {
-    let replacement_length = replacement.len();
+) -> io::Result<()> {
+    let end_index = start_index + original_token_as_bytes.len();
+    if original_bytes.get(start_index..end_index) != Some(original_token_as_bytes) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "The mutant's span {}..{} doesn't point at `{}`",
+                start_index,
+                end_index,
+                String::from_utf8_lossy(original_token_as_bytes)
+            ),
+        ));
+    }
+
+    original_bytes.splice(start_index..end_index, replacement.iter().cloned());
+    Ok(())
+}
 
-    if replacement_length == 0 && original_token_as_bytes[0] == 33 {
-        let bang_index = original_bytes.iter().position(|&r| r == 33).unwrap();
-        original_bytes.remove(bang_index);
-    } else {
-        match replacement_length {
-            1 => match replacement {
-                b">" | b"<" => {
-                    original_bytes.splice(
-                        start_index..start_index + replacement_length,
-                        replacement.iter().cloned(),
-                    );
-                    original_bytes.remove(start_index + 1);
-                }
-                _ => {
-                    original_bytes.splice(
-                        start_index..start_index + replacement_length,
-                        replacement.iter().cloned(),
-                    );
-                }
-            },
-            2 => match replacement {
-                b">=" | b"<=" => {
-                    original_bytes.insert(start_index, b' ');
-                    original_bytes.splice(
-                        start_index..start_index + replacement_length,
-                        replacement.iter().cloned(),
-                    );
-                }
-                _ => {
-                    original_bytes.splice(
-                        start_index..start_index + replacement_length,
-                        replacement.iter().cloned(),
-                    );
-                }
-            },
-            3 => {
-                original_bytes.splice(
-                    start_index..start_index + replacement_length,
-                    replacement.iter().cloned(),
-                );
-            }
-            _ => panic!("Invalid replacement length"),
+/// Puts the command in its own process group, so that everything it spawns can be killed together.
+pub fn isolate_process_group(command: &mut Command) -> &mut Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+}
+
+/// Waits for the child to exit and collects its output.
+/// Returns `None` if the timeout elapsed first, in which case the child's process tree is killed.
+pub fn wait_with_timeout(
+    mut child: Child,
+    timeout: Option<Duration>,
+) -> io::Result<Option<Output>> {
+    let Some(timeout) = timeout else {
+        return child.wait_with_output().map(Some);
+    };
+
+    // Drain the pipes while polling, so a chatty child can't block on a full pipe
+    let stdout = child.stdout.take().map(|mut out| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = out.read_to_end(&mut buf);
+            buf
+        })
+    });
+    let stderr = child.stderr.take().map(|mut err| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = err.read_to_end(&mut buf);
+            buf
+        })
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            kill_process_tree(&mut child);
+            return Ok(None);
         }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout.map_or_else(Vec::new, |h| h.join().unwrap_or_default()),
+        stderr: stderr.map_or_else(Vec::new, |h| h.join().unwrap_or_default()),
+    }))
+}
+
+fn kill_process_tree(child: &mut Child) {
+    // The child leads its own process group (see `isolate_process_group()`)
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
     }
+    let _ = child.kill();
+    let _ = child.wait();
 }
 
 #[cfg(test)]
@@ -188,6 +318,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_replace_bytes_mismatch() {
+        let mut original_bytes = b"a == b".to_vec();
+        assert!(replace_bytes(&mut original_bytes, 2, b"!=", b"==").is_err());
+        assert!(replace_bytes(&mut original_bytes, 5, b"==", b"!=").is_err());
+        assert_eq!(original_bytes, b"a == b");
+    }
+
     #[test]
     fn test_replace_bytes_equal() {
         let mut original_bytes = "==".as_bytes().to_vec();
@@ -199,7 +337,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"!=");
     }
 
@@ -214,7 +353,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"==");
     }
 
@@ -229,7 +369,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"<=");
     }
 
@@ -244,7 +385,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"assert(c as u64 <= x as u64);");
     }
 
@@ -259,7 +401,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"<");
     }
 
@@ -274,7 +417,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b">=");
     }
 
@@ -289,7 +433,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"assert(c as u64 >= x as u64);");
     }
 
@@ -304,7 +449,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b">");
     }
 
@@ -319,7 +465,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"|");
     }
 
@@ -334,7 +481,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"&");
     }
 
@@ -349,7 +497,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"&");
     }
 
@@ -364,7 +513,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b">>");
     }
 
@@ -379,7 +529,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"<<");
     }
 
@@ -394,7 +545,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"-");
     }
 
@@ -409,7 +561,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"+");
     }
 
@@ -424,7 +577,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"/");
     }
 
@@ -439,7 +593,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"*");
     }
 
@@ -454,7 +609,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"*");
     }
 
@@ -469,7 +625,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"--");
     }
 
@@ -484,7 +641,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"++");
     }
 
@@ -499,7 +657,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"-=");
     }
 
@@ -514,7 +673,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"+=");
     }
 
@@ -529,7 +689,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"/=");
     }
 
@@ -544,7 +705,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"*=");
     }
 
@@ -559,7 +721,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"*=");
     }
 
@@ -574,7 +737,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"|=");
     }
 
@@ -589,7 +753,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"&=");
     }
 
@@ -604,7 +769,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"&=");
     }
 
@@ -619,7 +785,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b">>=");
     }
 
@@ -634,7 +801,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"<<=");
     }
 
@@ -649,7 +817,8 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"");
     }
 
@@ -664,18 +833,71 @@ mod tests {
             start_index,
             original_token_as_bytes,
             replacement,
-        );
+        )
+        .unwrap();
         assert_eq!(original_bytes, b"true");
     }
 
+    #[test]
+    fn test_has_default() {
+        let declarations = Declarations {
+            defaults: HashSet::from(["Point".to_string()]),
+            ..Default::default()
+        };
+        for noir_type in [
+            "Field",
+            "u64",
+            "bool",
+            "Point",
+            "geometry::Point<Field>",
+            "[Point; 2]",
+            "(u8, [bool; 3])",
+            "Option<Line>",
+        ] {
+            assert!(declarations.has_default(noir_type), "{}", noir_type);
+        }
+        for noir_type in ["Line", "[Line; 2]", "(u8, Line)", "T", "str<5>"] {
+            assert!(!declarations.has_default(noir_type), "{}", noir_type);
+        }
+    }
+
     #[test]
     fn test_overlaps() {
         let filter_range = Range { start: 10, end: 20 };
 
         let token_range = Range { start: 12, end: 18 };
-        assert_eq!(overlaps(&filter_range, &token_range), true);
+        assert!(overlaps(&filter_range, &token_range));
 
         let token_range = Range { start: 25, end: 35 };
-        assert_eq!(overlaps(&filter_range, &token_range), false);
+        assert!(!overlaps(&filter_range, &token_range));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_with_timeout_kills_hanging_child() {
+        let child = isolate_process_group(Command::new("sleep").arg("5"))
+            .spawn()
+            .unwrap();
+        let start = Instant::now();
+        let output = wait_with_timeout(child, Some(Duration::from_millis(100))).unwrap();
+        assert!(output.is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_with_timeout_collects_output() {
+        let child = isolate_process_group(
+            Command::new("echo")
+                .arg("hunter")
+                .stdout(std::process::Stdio::piped()),
+        )
+        .spawn()
+        .unwrap();
+        let output = wait_with_timeout(child, Some(Duration::from_secs(5)))
+            .unwrap()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hunter\n");
     }
 }
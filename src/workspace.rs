@@ -0,0 +1,266 @@
+use std::{
+    fs, io,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex},
+};
+
+use fs_extra::error::Error;
+use tempfile::TempDir;
+
+use crate::{config::LanguageConfig, file_manager::find_project_root, handlers::mutator::Mutant};
+
+/// An isolated copy of the test project that a single mutant is built and tested in.
+pub struct Workspace {
+    dir: TempDir,
+    // The root of the user's project currently mirrored in `dir`
+    project_root: Option<PathBuf>,
+    // The original source file whose mutated copy is currently in `dir`
+    mutated: Option<(PathBuf, PathBuf)>,
+}
+
+impl Workspace {
+    pub fn new(config: &dyn LanguageConfig) -> Result<Workspace, Error> {
+        Ok(Workspace {
+            dir: config.setup_test_infrastructure()?,
+            project_root: None,
+            mutated: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Resets the workspace to a pristine copy of the mutant's project and
+    /// returns the path of the workspace file the mutation should be applied to.
+    pub fn prepare(&mut self, config: &dyn LanguageConfig, mutant: &Mutant) -> io::Result<PathBuf> {
+        let project_root = find_project_root(mutant.path(), config)?;
+        self.load_project(config, &project_root)?;
+
+        let temp_file = config.copy_src_file(self.path(), &project_root, mutant)?;
+        self.mutated = Some((mutant.path().canonicalize()?, temp_file.clone()));
+
+        Ok(temp_file)
+    }
+
+    /// Makes the workspace a pristine copy of the project at `project_root`.
+    pub fn load_project(
+        &mut self,
+        config: &dyn LanguageConfig,
+        project_root: &Path,
+    ) -> io::Result<()> {
+        if self.project_root.as_deref() != Some(project_root) {
+            self.clear()?;
+            config.copy_project(project_root, self.path())?;
+            self.project_root = Some(project_root.to_path_buf());
+        } else if let Some((original, copy)) = self.mutated.take() {
+            // Undo the previous mutant
+            fs::copy(original, copy)?;
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.project_root = None;
+        self.mutated = None;
+
+        for entry in fs::read_dir(self.path())? {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(path)?;
+            } else {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A fixed set of workspaces shared by the worker threads.
+/// Each mutant checks out a workspace for the duration of its build and test run,
+/// so no two mutants are ever compiled into the same project.
+pub struct WorkspacePool {
+    workspaces: Mutex<Vec<Workspace>>,
+    available: Condvar,
+}
+
+impl WorkspacePool {
+    pub fn new(size: usize, config: &dyn LanguageConfig) -> Result<WorkspacePool, Error> {
+        let workspaces = (0..size.max(1))
+            .map(|_| Workspace::new(config))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WorkspacePool {
+            workspaces: Mutex::new(workspaces),
+            available: Condvar::new(),
+        })
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|w| w.path().to_path_buf())
+            .collect()
+    }
+
+    /// Blocks until a workspace is free. The workspace is returned to the pool when the guard is dropped.
+    pub fn checkout(&self) -> WorkspaceGuard<'_> {
+        let mut workspaces = self.workspaces.lock().unwrap();
+        loop {
+            if let Some(workspace) = workspaces.pop() {
+                return WorkspaceGuard {
+                    pool: self,
+                    workspace: Some(workspace),
+                };
+            }
+            workspaces = self.available.wait(workspaces).unwrap();
+        }
+    }
+}
+
+pub struct WorkspaceGuard<'a> {
+    pool: &'a WorkspacePool,
+    workspace: Option<Workspace>,
+}
+
+impl Deref for WorkspaceGuard<'_> {
+    type Target = Workspace;
+
+    fn deref(&self) -> &Workspace {
+        self.workspace.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for WorkspaceGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Workspace {
+        self.workspace.as_mut().unwrap()
+    }
+}
+
+impl Drop for WorkspaceGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(workspace) = self.workspace.take() {
+            self.pool.workspaces.lock().unwrap().push(workspace);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::config, handlers::mutator::mutant_builder, languages::common::Language,
+        operators::OperatorConfig, token::Token,
+    };
+    use std::{thread, time::Duration};
+    use tempfile::tempdir;
+
+    const SOURCE: &str = "fn main() {\n    assert(1 < 2);\n}\n";
+
+    /// A Noir project with a single source file
+    fn project(name: &str) -> TempDir {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Nargo.toml"),
+            format!("[package]\nname = \"{}\"\ntype = \"bin\"\n", name),
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.nr"), SOURCE).unwrap();
+        dir
+    }
+
+    fn mutant(project: &Path) -> Mutant {
+        mutant_builder(
+            0,
+            Token::Less,
+            (25, 26),
+            project.join("src/main.nr"),
+            false,
+            None,
+            &OperatorConfig::default(),
+        )
+        .remove(0)
+    }
+
+    #[test]
+    fn test_checkout_and_release() {
+        let config = config(Language::Noir);
+        let pool = WorkspacePool::new(2, &*config).unwrap();
+        let paths = pool.paths();
+        assert_eq!(paths.len(), 2);
+        assert_ne!(paths[0], paths[1]);
+
+        let first = pool.checkout();
+        let second = pool.checkout();
+        assert_ne!(first.path(), second.path());
+        assert!(pool.paths().is_empty());
+
+        let released = first.path().to_path_buf();
+        drop(first);
+        assert_eq!(pool.paths(), vec![released.clone()]);
+        assert_eq!(pool.checkout().path(), released);
+        drop(second);
+        assert_eq!(pool.paths().len(), 2);
+    }
+
+    #[test]
+    fn test_checkout_waits_for_release() {
+        let config = config(Language::Noir);
+        let pool = WorkspacePool::new(1, &*config).unwrap();
+        let held = pool.checkout();
+        let held_path = held.path().to_path_buf();
+
+        thread::scope(|scope| {
+            let waiting = scope.spawn(|| pool.checkout().path().to_path_buf());
+            thread::sleep(Duration::from_millis(50));
+            assert!(!waiting.is_finished());
+            drop(held);
+            assert_eq!(waiting.join().unwrap(), held_path);
+        });
+    }
+
+    #[test]
+    fn test_load_project_restores_mutated_file() {
+        let config = config(Language::Noir);
+        let project = project("restore");
+        let mutant = mutant(project.path());
+        let mut workspace = Workspace::new(&*config).unwrap();
+
+        let temp_file = workspace.prepare(&*config, &mutant).unwrap();
+        assert_eq!(temp_file, workspace.path().join("src/main.nr"));
+        assert_eq!(fs::read_to_string(&temp_file).unwrap(), SOURCE);
+
+        fs::write(&temp_file, "fn main() {\n    assert(1 >= 2);\n}\n").unwrap();
+        let project_root = project.path().canonicalize().unwrap();
+        workspace.load_project(&*config, &project_root).unwrap();
+        assert_eq!(fs::read_to_string(&temp_file).unwrap(), SOURCE);
+        // The user's project is never touched
+        assert_eq!(
+            fs::read_to_string(project.path().join("src/main.nr")).unwrap(),
+            SOURCE
+        );
+    }
+
+    #[test]
+    fn test_load_project_replaces_other_project() {
+        let config = config(Language::Noir);
+        let first = project("first");
+        let second = project("second");
+        fs::write(first.path().join("src/lib.nr"), "").unwrap();
+        let mut workspace = Workspace::new(&*config).unwrap();
+
+        workspace.load_project(&*config, first.path()).unwrap();
+        assert!(workspace.path().join("src/lib.nr").is_file());
+
+        workspace.load_project(&*config, second.path()).unwrap();
+        assert!(!workspace.path().join("src/lib.nr").exists());
+        assert!(fs::read_to_string(workspace.path().join("Nargo.toml"))
+            .unwrap()
+            .contains("second"));
+    }
+}